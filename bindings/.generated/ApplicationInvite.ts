@@ -0,0 +1,19 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { PerUserInvite } from "./PerUserInvite";
+
+/**
+ * Settings for servers that review join applications before sending out an invite
+ */
+export type ApplicationInvite = { 
+/**
+ * The per-user invite to send to approved applicants
+ */
+invite: PerUserInvite, 
+/**
+ * The channel in which new applications are posted for review
+ */
+review_channel_id: string, 
+/**
+ * The questions applicants must answer
+ */
+questions: Array<string>, };
\ No newline at end of file
@@ -0,0 +1,10 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * An action to perform on the invite blacklist of a server
+ */
+export type BlacklistAction = { "List": Record<string, never> } | { "Add": { user_id: string, reason: string | null, 
+/**
+ * When the entry should expire, None for a permanent blacklist
+ */
+expires_at: string | null, } } | { "Remove": { user_id: string, } };
\ No newline at end of file
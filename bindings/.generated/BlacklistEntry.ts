@@ -0,0 +1,14 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * A user on the invite blacklist of a server
+ */
+export type BlacklistEntry = { user_id: string, reason: string | null, 
+/**
+ * When the entry expires, None if it is permanent
+ */
+expires_at: string | null, 
+/**
+ * The team member who blacklisted the user, None for entries from before reasons were tracked
+ */
+created_by: string | null, created_at: string, };
\ No newline at end of file
@@ -3,4 +3,12 @@
 /**
  * Represents the error that can occur when creating an invite for a user
  */
-export type CreateInviteForUserError = { "Generic": { message: string, } } | { "ServerNotFound": Record<string, never> } | { "ServerNeedsLoginForInvite": Record<string, never> } | { "UserIsBlacklisted": Record<string, never> } | { "ServerHasNoInvite": Record<string, never> } | { "ServerHasInvalidInvite": Record<string, never> } | { "ServerTypeNotApprovedOrCertified": Record<string, never> } | { "ServerStateNotPublic": Record<string, never> };
\ No newline at end of file
+export type CreateInviteForUserError = { "Generic": { message: string, } } | { "ServerNotFound": Record<string, never> } | { "ServerBotMissing": Record<string, never> } | { "ServerNeedsLoginForInvite": Record<string, never> } | { "InvitesPaused": { 
+/**
+ * None if invites are paused until resumed by the servers team
+ */
+until: string | null, } } | { "OutsideInviteWindow": { 
+/**
+ * When invites are next handed out, None if the schedule never opens
+ */
+next_open: string | null, } } | { "UserIsBlacklisted": Record<string, never> } | { "UserIsBannedFromServer": Record<string, never> } | { "UserNotWhitelisted": Record<string, never> } | { "AccountTooNew": { min_days: number, } } | { "VoteRequired": { hours: number, } } | { "TooManyListedServers": { max: number, } } | { "ApplicationAnswersRequired": { questions: Array<string>, } } | { "ApplicationAlreadyPending": Record<string, never> } | { "RateLimited": { retry_after: bigint, } } | { "DailyQuotaExceeded": { retry_after: bigint, } } | { "ServerHasNoInvite": Record<string, never> } | { "ServerHasInvalidInvite": Record<string, never> } | { "ServerInviteChannelBroken": Record<string, never> } | { "ServerTypeNotApprovedOrCertified": Record<string, never> } | { "ServerStateNotPublic": Record<string, never> };
\ No newline at end of file
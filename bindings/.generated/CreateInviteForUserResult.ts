@@ -3,4 +3,4 @@
 /**
  * Represents the result of creating an invite for a user
  */
-export type CreateInviteForUserResult = { "Invite": { url: string, } };
\ No newline at end of file
+export type CreateInviteForUserResult = { "Invite": { url: string, } } | { "ApplicationSubmitted": { application_id: string, } };
\ No newline at end of file
@@ -10,6 +10,30 @@ err: CreateInviteForUserError,
  * Error message
  */
 message: string, } } | { "ResolveInvite": { 
+/**
+ * The error that occured
+ */
+message: string, } } | { "ManageWhitelist": { 
+/**
+ * The error that occured
+ */
+message: string, } } | { "GetInviteLogs": { 
+/**
+ * The error that occured
+ */
+message: string, } } | { "ManageBlacklist": { 
+/**
+ * The error that occured
+ */
+message: string, } } | { "ManageInviteRules": { 
+/**
+ * The error that occured
+ */
+message: string, } } | { "ManageInviteSchedule": { 
+/**
+ * The error that occured
+ */
+message: string, } } | { "GetServerStats": { 
 /**
  * The error that occured
  */
@@ -1,3 +1,7 @@
 // This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { BlacklistAction } from "./BlacklistAction";
+import type { InviteRule } from "./InviteRule";
+import type { InviteScheduleAction } from "./InviteScheduleAction";
+import type { WhitelistAction } from "./WhitelistAction";
 
-export type InfernoplexQuery = { "CreateInvite": { session: string | null, guild_id: string, } } | { "ResolveInvite": { invite_code: string, guild_id: string, } };
\ No newline at end of file
+export type InfernoplexQuery = { "CreateInvite": { session: string | null, guild_id: string, answers: Array<string> | null, } } | { "ResolveInvite": { invite_code: string, guild_id: string, } } | { "ManageWhitelist": { session: string, guild_id: string, action: WhitelistAction, } } | { "GetInviteLogs": { session: string, guild_id: string, limit: bigint | null, offset: bigint | null, } } | { "ManageBlacklist": { session: string, guild_id: string, action: BlacklistAction, } } | { "ManageInviteRules": { session: string, guild_id: string, rules: Array<InviteRule> | null, } } | { "ManageInviteSchedule": { session: string, guild_id: string, action: InviteScheduleAction, } } | { "GetServerStats": { guild_id: string, days: number | null, } };
\ No newline at end of file
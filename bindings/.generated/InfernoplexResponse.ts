@@ -1,8 +1,25 @@
 // This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { BlacklistEntry } from "./BlacklistEntry";
 import type { CreateInviteForUserResult } from "./CreateInviteForUserResult";
+import type { InviteLogEntry } from "./InviteLogEntry";
+import type { InviteRule } from "./InviteRule";
+import type { InviteSchedule } from "./InviteSchedule";
+import type { MemberStatsSample } from "./MemberStatsSample";
 
 export type InfernoplexResponse = { "CreateInvite": { 
 /**
  * Successfully created an invite
  */
-result: CreateInviteForUserResult, } } | { "ResolveInvite": Record<string, never> };
\ No newline at end of file
+result: CreateInviteForUserResult, } } | { "ResolveInvite": Record<string, never> } | { "ManageWhitelist": { 
+/**
+ * The whitelisted users (after performing the action)
+ */
+users: Array<string>, } } | { "GetInviteLogs": { logs: Array<InviteLogEntry>, } } | { "ManageBlacklist": { 
+/**
+ * The blacklist (after performing the action)
+ */
+entries: Array<BlacklistEntry>, } } | { "ManageInviteRules": { rules: Array<InviteRule>, } } | { "ManageInviteSchedule": { 
+/**
+ * The schedule (after performing the action), None if invites are handed out at all times
+ */
+schedule: InviteSchedule | null, } } | { "GetServerStats": { samples: Array<MemberStatsSample>, } };
\ No newline at end of file
@@ -0,0 +1,18 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * An entry in the invite log of a server
+ */
+export type InviteLogEntry = { id: string, 
+/**
+ * The user the invite was handed out to, None if anonymous
+ */
+user_id: string | null, 
+/**
+ * The invite code, or the invite URL for InviteUrl invites
+ */
+invite: string, 
+/**
+ * The invite mode of the server at the time
+ */
+mode: string, max_uses: number | null, max_age: number | null, created_at: string, };
\ No newline at end of file
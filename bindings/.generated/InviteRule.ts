@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * A rule a user must satisfy to get an invite to a server
+ */
+export type InviteRule = { "MinAccountAge": { days: number, } } | { "RecentVote": { hours: number, } } | { "MaxListedServers": { count: number, } };
\ No newline at end of file
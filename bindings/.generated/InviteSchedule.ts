@@ -0,0 +1,11 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { InviteWindow } from "./InviteWindow";
+
+/**
+ * The weekly schedule in which a server accepts new members through Infinity List
+ */
+export type InviteSchedule = { 
+/**
+ * An IANA timezone name, such as ``Europe/Berlin``
+ */
+timezone: string, windows: Array<InviteWindow>, };
@@ -0,0 +1,7 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { InviteSchedule } from "./InviteSchedule";
+
+/**
+ * An action to perform on the invite schedule of a server
+ */
+export type InviteScheduleAction = { "Get": Record<string, never> } | { "Set": { schedule: InviteSchedule, } } | { "Clear": Record<string, never> };
\ No newline at end of file
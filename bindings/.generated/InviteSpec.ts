@@ -0,0 +1,8 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { ApplicationInvite } from "./ApplicationInvite";
+import type { PerUserInvite } from "./PerUserInvite";
+
+/**
+ * Represents how invites to a server are created
+ */
+export type InviteSpec = { "None": Record<string, never> } | { "InviteUrl": { url: string, } } | { "Vanity": { code: string, } } | { "PerUser": PerUserInvite } | { "Whitelist": PerUserInvite } | { "Application": ApplicationInvite };
\ No newline at end of file
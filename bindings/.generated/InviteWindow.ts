@@ -0,0 +1,18 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * A weekly time range in which invites are handed out
+ */
+export type InviteWindow = { 
+/**
+ * The day of the week, 0 = Monday and 6 = Sunday
+ */
+day: number, 
+/**
+ * Minutes since midnight at which the window opens
+ */
+start: number, 
+/**
+ * Minutes since midnight at which the window closes (at most 1440)
+ */
+end: number, };
@@ -0,0 +1,10 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * A sample of the member counts of a server
+ */
+export type MemberStatsSample = { total_members: number, online_members: number, 
+/**
+ * Whether this is the daily average of older samples
+ */
+daily: boolean, created_at: string, };
\ No newline at end of file
@@ -0,0 +1,30 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Settings for invites that Infinity List creates on a per-user basis
+ */
+export type PerUserInvite = { 
+/**
+ * The channel to create the invite in
+ */
+channel_id: string, 
+/**
+ * The maximum number of uses of the invite (0 = unlimited)
+ */
+max_uses: number, 
+/**
+ * The maximum age of the invite in seconds (0 = never expires)
+ */
+max_age: number, 
+/**
+ * Channels to try, in order, if the invite cannot be created in ``channel_id``
+ */
+fallback_channel_ids: string[], 
+/**
+ * Whether the invite only grants temporary membership (members are kicked when they go offline without a role)
+ */
+temporary: boolean, 
+/**
+ * The scheduled event the invite points to, if any
+ */
+event_id: string | null, };
\ No newline at end of file
@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * An action to perform on the invite whitelist of a server
+ */
+export type WhitelistAction = { "List": Record<string, never> } | { "Add": { user_id: string, } } | { "Remove": { user_id: string, } };
\ No newline at end of file
@@ -0,0 +1,25 @@
+-- Migrates legacy colon-delimited invites in servers.invite to the versioned InviteSpec JSON format
+--
+-- Infernoplex can still read legacy invites, so this can be run at any time after deploying
+
+UPDATE servers SET invite = '{"version":1,"spec":{"None":{}}}' WHERE invite = 'none';
+
+UPDATE servers SET invite = jsonb_build_object(
+    'version', 1,
+    'spec', jsonb_build_object(
+        'InviteUrl', jsonb_build_object('url', substring(invite FROM length('invite_url:') + 1))
+    )
+)::text WHERE invite LIKE 'invite_url:%';
+
+-- Malformed max_uses/max_age fall back to their defaults instead of failing the cast (and with it the migration).
+-- Invites with a malformed channel ID are left as is
+UPDATE servers SET invite = jsonb_build_object(
+    'version', 1,
+    'spec', jsonb_build_object(
+        'PerUser', jsonb_build_object(
+            'channel_id', split_part(invite, ':', 2),
+            'max_uses', CASE WHEN split_part(invite, ':', 3) ~ '^[0-9]{1,3}$' THEN split_part(invite, ':', 3)::int ELSE 1 END,
+            'max_age', CASE WHEN split_part(invite, ':', 4) ~ '^[0-9]{1,9}$' THEN split_part(invite, ':', 4)::int ELSE 300 END
+        )
+    )
+)::text WHERE invite LIKE 'per_user:%' AND split_part(invite, ':', 2) ~ '^[0-9]{1,20}$';
@@ -0,0 +1,2 @@
+-- Adds the invite whitelist used by the Whitelist invite mode
+ALTER TABLE servers ADD COLUMN whitelisted_users TEXT[] NOT NULL DEFAULT '{}';
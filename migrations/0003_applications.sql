@@ -0,0 +1,15 @@
+-- Adds the join application queue used by the Application invite mode
+CREATE TABLE server_applications (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    server_id TEXT NOT NULL REFERENCES servers(server_id) ON DELETE CASCADE,
+    user_id TEXT NOT NULL,
+    questions TEXT[] NOT NULL,
+    answers TEXT[] NOT NULL,
+    state TEXT NOT NULL DEFAULT 'pending',
+    reviewed_by TEXT,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    reviewed_at TIMESTAMPTZ
+);
+
+-- A user can only have one pending application per server
+CREATE UNIQUE INDEX server_applications_pending_idx ON server_applications (server_id, user_id) WHERE state = 'pending';
@@ -0,0 +1,14 @@
+-- Tracks the per-user invites infernoplex has created so they can be reused while still valid
+CREATE TABLE server_invites (
+    code TEXT PRIMARY KEY,
+    server_id TEXT NOT NULL REFERENCES servers(server_id) ON DELETE CASCADE,
+    user_id TEXT, -- NULL if the invite was created for an anonymous user
+    channel_id TEXT NOT NULL,
+    max_uses INTEGER NOT NULL, -- 0 = unlimited
+    uses INTEGER NOT NULL DEFAULT 0,
+    expires_at TIMESTAMPTZ, -- NULL = never expires
+    deleted BOOLEAN NOT NULL DEFAULT false,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX server_invites_server_user_idx ON server_invites (server_id, user_id);
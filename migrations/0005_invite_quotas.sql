@@ -0,0 +1,10 @@
+-- Adds an optional daily invite quota to servers (NULL = no quota)
+ALTER TABLE servers ADD COLUMN daily_invite_quota INTEGER;
+
+-- Number of invites created per server per day (UTC)
+CREATE TABLE server_invite_quotas (
+    server_id TEXT NOT NULL REFERENCES servers(server_id) ON DELETE CASCADE,
+    day DATE NOT NULL,
+    count INTEGER NOT NULL DEFAULT 0,
+    PRIMARY KEY (server_id, day)
+);
@@ -0,0 +1,13 @@
+-- Ledger of every invite handed out by infernoplex
+CREATE TABLE server_invite_logs (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    server_id TEXT NOT NULL REFERENCES servers(server_id) ON DELETE CASCADE,
+    user_id TEXT, -- NULL if the invite was handed out to an anonymous user
+    invite TEXT NOT NULL, -- The invite code, or the invite URL for InviteUrl invites
+    mode TEXT NOT NULL,
+    max_uses INTEGER,
+    max_age INTEGER,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX server_invite_logs_server_idx ON server_invite_logs (server_id, created_at DESC);
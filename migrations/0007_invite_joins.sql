@@ -0,0 +1,12 @@
+-- Joins attributed to invites handed out by Infinity List
+CREATE TABLE server_invite_joins (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    server_id TEXT NOT NULL REFERENCES servers(server_id) ON DELETE CASCADE,
+    user_id TEXT NOT NULL,
+    code TEXT NOT NULL,
+    source TEXT NOT NULL, -- 'issued' for invites created by infernoplex, 'invite_url' for the servers fixed invite
+    invite_user_id TEXT, -- The user the invite was created for, if any
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX server_invite_joins_server_idx ON server_invite_joins (server_id, created_at DESC);
@@ -0,0 +1,7 @@
+-- What to do when someone joins using a per-user invite that was created for someone else
+ALTER TABLE servers ADD COLUMN invite_mismatch_action TEXT NOT NULL DEFAULT 'none' CHECK (invite_mismatch_action IN ('none', 'alert', 'kick'));
+
+-- Channel to post alerts about the server to
+ALTER TABLE servers ADD COLUMN alert_channel TEXT;
+
+ALTER TABLE server_invite_joins ADD COLUMN mismatch BOOLEAN NOT NULL DEFAULT false;
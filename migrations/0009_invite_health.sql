@@ -0,0 +1,4 @@
+-- Result of the last invite health check (see the invitehealth task)
+ALTER TABLE servers ADD COLUMN invite_health TEXT NOT NULL DEFAULT 'unknown' CHECK (invite_health IN ('unknown', 'ok', 'broken'));
+ALTER TABLE servers ADD COLUMN invite_health_message TEXT;
+ALTER TABLE servers ADD COLUMN invite_health_checked_at TIMESTAMPTZ;
@@ -0,0 +1,14 @@
+-- Blacklist entries with a reason and optional expiry. servers.blacklisted_users is kept in sync with this table
+CREATE TABLE server_blacklist (
+    server_id TEXT NOT NULL REFERENCES servers(server_id) ON DELETE CASCADE,
+    user_id TEXT NOT NULL,
+    reason TEXT,
+    expires_at TIMESTAMPTZ,
+    created_by TEXT,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (server_id, user_id)
+);
+
+INSERT INTO server_blacklist (server_id, user_id)
+SELECT server_id, UNNEST(blacklisted_users) FROM servers
+ON CONFLICT DO NOTHING;
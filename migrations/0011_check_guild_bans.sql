@@ -0,0 +1,2 @@
+-- Whether users banned from the guild on Discord should be refused invites (needs the Ban Members permission)
+ALTER TABLE servers ADD COLUMN check_guild_bans BOOLEAN NOT NULL DEFAULT false;
@@ -0,0 +1,2 @@
+-- Eligibility rules evaluated before handing out an invite (a JSON array of InviteRule)
+ALTER TABLE servers ADD COLUMN invite_rules JSONB NOT NULL DEFAULT '[]';
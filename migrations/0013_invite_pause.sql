@@ -0,0 +1,8 @@
+-- Invite kill switch. Invites are paused while invites_paused is set and invites_paused_until is NULL (until resumed) or in the future
+ALTER TABLE servers ADD COLUMN invites_paused BOOLEAN NOT NULL DEFAULT false;
+ALTER TABLE servers ADD COLUMN invites_paused_until TIMESTAMPTZ;
+
+-- Automatic raid pause: pause invites for raid_pause_minutes once raid_join_threshold attributed joins happen within raid_window_secs (NULL = disabled)
+ALTER TABLE servers ADD COLUMN raid_join_threshold INTEGER;
+ALTER TABLE servers ADD COLUMN raid_window_secs INTEGER NOT NULL DEFAULT 300;
+ALTER TABLE servers ADD COLUMN raid_pause_minutes INTEGER NOT NULL DEFAULT 60;
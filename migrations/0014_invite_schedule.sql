@@ -0,0 +1,2 @@
+-- Weekly windows in which invites are handed out (a JSON InviteSchedule), NULL = always
+ALTER TABLE servers ADD COLUMN invite_schedule JSONB;
@@ -0,0 +1,2 @@
+-- Whether a per-user invite grants temporary membership, so that invites are only reused for the same kind of membership
+ALTER TABLE server_invites ADD COLUMN temporary BOOLEAN NOT NULL DEFAULT false;
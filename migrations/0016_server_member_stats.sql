@@ -0,0 +1,9 @@
+-- Timeseries of the member counts of listed servers. Hourly samples are downsampled to daily averages after a week
+CREATE TABLE server_member_stats (
+    server_id TEXT NOT NULL REFERENCES servers(server_id) ON DELETE CASCADE,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    total_members INTEGER NOT NULL,
+    online_members INTEGER NOT NULL,
+    daily BOOLEAN NOT NULL DEFAULT false, -- Whether this is a downsampled daily average
+    PRIMARY KEY (server_id, created_at)
+);
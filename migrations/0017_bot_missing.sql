@@ -0,0 +1,4 @@
+-- Set while Infinity List is not in a listed server. Servers missing the bot for longer than the grace period are made private,
+-- with their previous state kept in bot_missing_previous_state so it can be restored once the bot is re-added
+ALTER TABLE servers ADD COLUMN bot_missing_since TIMESTAMPTZ;
+ALTER TABLE servers ADD COLUMN bot_missing_previous_state TEXT;
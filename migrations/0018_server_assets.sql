@@ -0,0 +1,8 @@
+-- SHA-256 hashes of the guild images last written to the CDN, so unchanged images are not re-encoded
+CREATE TABLE server_assets (
+    server_id TEXT NOT NULL REFERENCES servers(server_id) ON DELETE CASCADE,
+    kind TEXT NOT NULL, -- icon, banner or splash
+    hash TEXT NOT NULL,
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (server_id, kind)
+);
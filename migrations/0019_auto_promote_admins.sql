@@ -0,0 +1,2 @@
+-- Whether members who become administrators after /setup are added to the servers team automatically
+ALTER TABLE servers ADD COLUMN auto_promote_admins BOOLEAN NOT NULL DEFAULT false;
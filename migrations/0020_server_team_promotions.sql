@@ -0,0 +1,8 @@
+-- Members Infinity List added to a servers team automatically after becoming an administrator. A member listed
+-- here who is no longer on the team was removed from it explicitly, and isn't added back automatically
+CREATE TABLE server_team_promotions (
+    server_id TEXT NOT NULL REFERENCES servers (server_id) ON DELETE CASCADE,
+    user_id TEXT NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (server_id, user_id)
+);
@@ -0,0 +1,5 @@
+-- server_blacklist is the only blacklist store infernoplex reads and writes now. Carry over entries added to
+-- servers.blacklisted_users since 0010. The column itself belongs to the shared schema and is left in place
+INSERT INTO server_blacklist (server_id, user_id)
+SELECT server_id, UNNEST(blacklisted_users) FROM servers
+ON CONFLICT DO NOTHING;
@@ -0,0 +1,229 @@
+use crate::shadowclaw::perms::check_for_permission;
+use crate::{Context, Error};
+use poise::{
+    serenity_prelude::{AutocompleteChoice, CreateAutocompleteResponse, CreateEmbed},
+    CreateReply,
+};
+use serenity::all::Mentionable;
+
+async fn _blacklist_check(ctx: Context<'_>) -> Result<bool, Error> {
+    check_for_permission(&ctx, "server.blacklist").await?;
+    Ok(true)
+}
+
+/// Suggests the users currently on the blacklist
+async fn autocomplete_blacklisted<'a>(
+    ctx: Context<'_>,
+    partial: &'a str,
+) -> CreateAutocompleteResponse<'a> {
+    let Some(guild_id) = ctx.guild_id() else {
+        return CreateAutocompleteResponse::new();
+    };
+
+    // Checks don't run for autocomplete, so don't leak the blacklist to members without access to it
+    if check_for_permission(&ctx, "server.blacklist")
+        .await
+        .is_err()
+    {
+        return CreateAutocompleteResponse::new();
+    }
+
+    let blacklist = match crate::shadowclaw::blacklist::search_blacklist(
+        &ctx.data().pool,
+        guild_id,
+        partial.trim(),
+        25,
+    )
+    .await
+    {
+        Ok(blacklist) => blacklist,
+        Err(e) => {
+            log::error!("Failed to fetch blacklist for autocomplete: {}", e);
+            return CreateAutocompleteResponse::new();
+        }
+    };
+
+    let choices = blacklist
+        .into_iter()
+        .map(|e| {
+            let mut name = match e.reason {
+                Some(reason) => format!("{} ({})", e.user_id, reason),
+                None => e.user_id.clone(),
+            };
+
+            if name.chars().count() > 100 {
+                name = name.chars().take(97).collect::<String>() + "...";
+            }
+
+            AutocompleteChoice::new(name, e.user_id)
+        })
+        .collect::<Vec<_>>();
+
+    CreateAutocompleteResponse::new().set_choices(choices)
+}
+
+/// Manage the users who cannot get an invite to your server, needs 'server.blacklist' permissions
+#[poise::command(
+    prefix_command,
+    slash_command,
+    subcommands("blacklist_add", "blacklist_remove", "blacklist_list"),
+    subcommand_required
+)]
+pub async fn blacklist(_ctx: Context<'_>) -> Result<(), Error> {
+    Ok(())
+}
+
+/// Add a user to the blacklist
+#[poise::command(
+    prefix_command,
+    slash_command,
+    rename = "add",
+    check = "_blacklist_check"
+)]
+pub async fn blacklist_add(
+    ctx: Context<'_>,
+    #[description = "The user to blacklist"] user: serenity::all::User,
+    #[description = "Why the user is being blacklisted"] reason: Option<String>,
+    #[description = "How many days the blacklist should last for (default: forever)"]
+    #[min = 1]
+    #[max = 36500]
+    days: Option<u32>,
+) -> Result<(), Error> {
+    let Some(guild_id) = ctx.guild_id() else {
+        return Err("This command can only be executed in a server".into());
+    };
+
+    let expires_at = match days {
+        Some(days) => Some(
+            chrono::Duration::try_days(days.into())
+                .and_then(|d| chrono::Utc::now().checked_add_signed(d))
+                .ok_or("The blacklist cannot last that long")?,
+        ),
+        None => None,
+    };
+
+    crate::shadowclaw::blacklist::add_to_blacklist(
+        &ctx.serenity_context().http,
+        &ctx.data().pool,
+        guild_id,
+        user.id,
+        reason,
+        expires_at,
+        ctx.author().id,
+    )
+    .await?;
+
+    ctx.send(
+        CreateReply::new().embed(CreateEmbed::new().title("Blacklist Updated").description(
+            match expires_at {
+                Some(expires_at) => format!(
+                    "{} has been blacklisted until <t:{}:f>",
+                    user.id.mention(),
+                    expires_at.timestamp()
+                ),
+                None => format!("{} has been blacklisted", user.id.mention()),
+            },
+        )),
+    )
+    .await?;
+
+    Ok(())
+}
+
+/// Remove a user from the blacklist
+#[poise::command(
+    prefix_command,
+    slash_command,
+    rename = "remove",
+    check = "_blacklist_check"
+)]
+pub async fn blacklist_remove(
+    ctx: Context<'_>,
+    #[description = "The ID of the user to remove from the blacklist"]
+    #[autocomplete = "autocomplete_blacklisted"]
+    user_id: String,
+) -> Result<(), Error> {
+    let Some(guild_id) = ctx.guild_id() else {
+        return Err("This command can only be executed in a server".into());
+    };
+
+    let user_id = user_id
+        .trim()
+        .parse::<serenity::all::UserId>()
+        .map_err(|_| "Please enter a valid user ID")?;
+
+    crate::shadowclaw::blacklist::remove_from_blacklist(&ctx.data().pool, guild_id, user_id)
+        .await?;
+
+    ctx.send(
+        CreateReply::new().embed(CreateEmbed::new().title("Blacklist Updated").description(
+            format!("{} has been removed from the blacklist", user_id.mention()),
+        )),
+    )
+    .await?;
+
+    Ok(())
+}
+
+/// List all blacklisted users
+#[poise::command(
+    prefix_command,
+    slash_command,
+    rename = "list",
+    check = "_blacklist_check"
+)]
+pub async fn blacklist_list(ctx: Context<'_>) -> Result<(), Error> {
+    let Some(guild_id) = ctx.guild_id() else {
+        return Err("This command can only be executed in a server".into());
+    };
+
+    let blacklist = crate::shadowclaw::blacklist::get_blacklist(&ctx.data().pool, guild_id).await?;
+
+    if blacklist.is_empty() {
+        ctx.say("No users are blacklisted").await?;
+        return Ok(());
+    }
+
+    let mut response = String::new();
+
+    for entry in blacklist {
+        let mut next_str = format!("- <@{}> ({})", entry.user_id, entry.user_id);
+
+        if let Some(reason) = entry.reason {
+            next_str.push_str(&format!(": {}", reason));
+        }
+
+        if let Some(expires_at) = entry.expires_at {
+            next_str.push_str(&format!(" [expires <t:{}:R>]", expires_at.timestamp()));
+        }
+
+        next_str.push('\n');
+
+        if response.len() + next_str.len() > 4000 {
+            ctx.send(
+                CreateReply::new().embed(
+                    CreateEmbed::new()
+                        .title("Blacklisted Users")
+                        .description(response.clone()),
+                ),
+            )
+            .await?;
+            response.clear();
+        }
+
+        response.push_str(&next_str);
+    }
+
+    if !response.is_empty() {
+        ctx.send(
+            CreateReply::new().embed(
+                CreateEmbed::new()
+                    .title("Blacklisted Users")
+                    .description(response),
+            ),
+        )
+        .await?;
+    }
+
+    Ok(())
+}
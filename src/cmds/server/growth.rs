@@ -0,0 +1,99 @@
+use crate::shadowclaw::memberstats::MemberStatsSample;
+use crate::{Context, Error};
+use poise::{serenity_prelude::CreateEmbed, CreateReply};
+
+const SPARKLINE_BLOCKS: [char; 8] = ['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+
+/// Renders the total member count of each day as a sparkline
+fn sparkline(samples: &[MemberStatsSample]) -> String {
+    let mut daily: Vec<(chrono::NaiveDate, i32)> = Vec::new();
+
+    for sample in samples {
+        let day = sample.created_at.date_naive();
+
+        match daily.last_mut() {
+            Some((last_day, total)) if *last_day == day => *total = sample.total_members,
+            _ => daily.push((day, sample.total_members)),
+        }
+    }
+
+    let min = daily.iter().map(|(_, t)| *t).min().unwrap_or(0);
+    let max = daily.iter().map(|(_, t)| *t).max().unwrap_or(0);
+
+    daily
+        .iter()
+        .map(|(_, total)| {
+            if max == min {
+                SPARKLINE_BLOCKS[0]
+            } else {
+                let idx =
+                    (total - min) as usize * (SPARKLINE_BLOCKS.len() - 1) / (max - min) as usize;
+                SPARKLINE_BLOCKS[idx]
+            }
+        })
+        .collect()
+}
+
+/// Formats the change in total members since ``days`` days ago
+fn change_since(samples: &[MemberStatsSample], current: i32, days: i64) -> String {
+    let since = chrono::Utc::now() - chrono::Duration::days(days);
+
+    match samples.iter().find(|s| s.created_at >= since) {
+        Some(sample) => format!("{:+}", current - sample.total_members),
+        None => "Not enough data".to_string(),
+    }
+}
+
+/// See how the member count of your server has changed over time
+#[poise::command(prefix_command, slash_command, guild_cooldown = 3)]
+pub async fn growth(
+    ctx: Context<'_>,
+    #[description = "How many days of history to summarize (default: 30)"]
+    #[min = 1]
+    #[max = 365]
+    days: Option<i32>,
+) -> Result<(), Error> {
+    let Some(guild_id) = ctx.guild_id() else {
+        return Err("This command can only be used in a server.".into());
+    };
+
+    let days = days.unwrap_or(30);
+
+    let samples =
+        crate::shadowclaw::memberstats::get_member_stats(&ctx.data().pool, guild_id, days).await?;
+
+    let Some(latest) = samples.last() else {
+        return Err("No member count history has been recorded for this server yet, please check back in an hour".into());
+    };
+
+    let peak_online = samples.iter().map(|s| s.online_members).max().unwrap_or(0);
+
+    let mut embed = CreateEmbed::new()
+        .title("Server Growth")
+        .description(format!(
+            "Member count history over the last {} days\n\n{}",
+            days,
+            sparkline(&samples)
+        ))
+        .field("Total Members", latest.total_members.to_string(), true)
+        .field("Online Members", latest.online_members.to_string(), true)
+        .field("Peak Online", peak_online.to_string(), true);
+
+    for (name, period) in [
+        ("Last 24 Hours", 1),
+        ("Last 7 Days", 7),
+        ("Last 30 Days", 30),
+    ] {
+        if i64::from(days) >= period {
+            embed = embed.field(
+                name,
+                change_since(&samples, latest.total_members, period),
+                true,
+            );
+        }
+    }
+
+    ctx.send(CreateReply::new().embed(embed)).await?;
+
+    Ok(())
+}
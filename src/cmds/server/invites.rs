@@ -0,0 +1,306 @@
+use crate::shadowclaw::inviterules::InviteRule;
+use crate::shadowclaw::perms::check_for_permission;
+use crate::{Context, Error};
+use poise::{serenity_prelude::CreateEmbed, CreateReply};
+
+async fn _invites_log_check(ctx: Context<'_>) -> Result<bool, Error> {
+    check_for_permission(&ctx, "server.view_invite_logs").await?;
+    Ok(true)
+}
+
+async fn _invites_pause_check(ctx: Context<'_>) -> Result<bool, Error> {
+    check_for_permission(&ctx, "server.pause_invites").await?;
+    Ok(true)
+}
+
+async fn _invites_edit_check(ctx: Context<'_>) -> Result<bool, Error> {
+    check_for_permission(&ctx, "server.edit").await?;
+    Ok(true)
+}
+
+/// Manage the invites Infinity List hands out for your server
+#[poise::command(
+    prefix_command,
+    slash_command,
+    subcommands(
+        "invites_log",
+        "invites_joins",
+        "invites_rules",
+        "invites_pause",
+        "invites_resume"
+    ),
+    subcommand_required
+)]
+pub async fn invites(_ctx: Context<'_>) -> Result<(), Error> {
+    Ok(())
+}
+
+/// View the invites handed out for your server, needs 'server.view_invite_logs' permissions
+#[poise::command(
+    prefix_command,
+    slash_command,
+    rename = "log",
+    check = "_invites_log_check"
+)]
+pub async fn invites_log(
+    ctx: Context<'_>,
+    #[description = "How many results to render."] limit: Option<i64>,
+    #[description = "How many results to skip."] offset: Option<i64>,
+) -> Result<(), Error> {
+    let Some(guild_id) = ctx.guild_id() else {
+        return Err("This command can only be executed in a server".into());
+    };
+
+    let logs = crate::shadowclaw::invitelog::get_invite_logs(
+        &ctx.data().pool,
+        guild_id,
+        limit.unwrap_or(25),
+        offset.unwrap_or(0),
+    )
+    .await?;
+
+    if logs.is_empty() {
+        ctx.say("No invites have been handed out yet").await?;
+        return Ok(());
+    }
+
+    let mut response = String::new();
+
+    let mut page = 1;
+    for entry in logs {
+        let next_str = format!(
+            "<t:{}:R> - {} - `{}` ({})\n",
+            entry.created_at.timestamp(),
+            match entry.user_id {
+                Some(user_id) => format!("<@{}>", user_id),
+                None => "Anonymous".to_string(),
+            },
+            entry.invite,
+            entry.mode
+        );
+
+        if response.len() + next_str.len() > 4000 {
+            ctx.send(
+                CreateReply::new().embed(
+                    CreateEmbed::new()
+                        .title(format!("Invite Log (Page {})", page))
+                        .description(response.clone()),
+                ),
+            )
+            .await?;
+            page += 1;
+            response.clear();
+        }
+
+        response.push_str(&next_str);
+    }
+
+    if !response.is_empty() {
+        ctx.send(
+            CreateReply::new().embed(
+                CreateEmbed::new()
+                    .title(format!("Invite Log (Page {})", page))
+                    .description(response),
+            ),
+        )
+        .await?;
+    }
+
+    Ok(())
+}
+
+/// See how many members joined through Infinity List, needs 'server.view_invite_logs' permissions
+#[poise::command(
+    prefix_command,
+    slash_command,
+    rename = "joins",
+    check = "_invites_log_check"
+)]
+pub async fn invites_joins(ctx: Context<'_>) -> Result<(), Error> {
+    let Some(guild_id) = ctx.guild_id() else {
+        return Err("This command can only be executed in a server".into());
+    };
+
+    let pool = &ctx.data().pool;
+
+    let mut embed = CreateEmbed::new()
+        .title("Joins Through Infinity List")
+        .description("Members who joined this server using an invite handed out by Infinity List");
+
+    for (name, days) in [
+        ("Last 24 Hours", Some(1)),
+        ("Last 7 Days", Some(7)),
+        ("Last 30 Days", Some(30)),
+        ("All Time", None),
+    ] {
+        let count =
+            crate::shadowclaw::tracker::count_attributed_joins(pool, guild_id, days).await?;
+        embed = embed.field(name, count.to_string(), true);
+    }
+
+    ctx.send(CreateReply::new().embed(embed)).await?;
+
+    Ok(())
+}
+
+/// View or change the rules users must satisfy to get an invite, needs 'server.edit' permissions
+#[poise::command(
+    prefix_command,
+    slash_command,
+    rename = "rules",
+    check = "_invites_edit_check"
+)]
+pub async fn invites_rules(
+    ctx: Context<'_>,
+    #[description = "Minimum age of the users Discord account in days (0 = no minimum)"]
+    min_account_age_days: Option<u32>,
+    #[description = "Require a vote for this server in the last N hours (0 = no vote needed)"]
+    recent_vote_hours: Option<u32>,
+    #[description = "Maximum number of other Infinity List servers the user may be in (-1 = no limit)"]
+    max_listed_servers: Option<i64>,
+) -> Result<(), Error> {
+    let Some(guild_id) = ctx.guild_id() else {
+        return Err("This command can only be executed in a server".into());
+    };
+
+    let pool = &ctx.data().pool;
+
+    let mut rules = crate::shadowclaw::inviterules::get_invite_rules(pool, guild_id).await?;
+
+    if min_account_age_days.is_some() || recent_vote_hours.is_some() || max_listed_servers.is_some()
+    {
+        if let Some(days) = min_account_age_days {
+            rules.retain(|r| !matches!(r, InviteRule::MinAccountAge { .. }));
+
+            if days > 0 {
+                rules.push(InviteRule::MinAccountAge { days });
+            }
+        }
+
+        if let Some(hours) = recent_vote_hours {
+            rules.retain(|r| !matches!(r, InviteRule::RecentVote { .. }));
+
+            if hours > 0 {
+                rules.push(InviteRule::RecentVote { hours });
+            }
+        }
+
+        if let Some(count) = max_listed_servers {
+            rules.retain(|r| !matches!(r, InviteRule::MaxListedServers { .. }));
+
+            if count >= 0 {
+                rules.push(InviteRule::MaxListedServers {
+                    count: u32::try_from(count)?,
+                });
+            }
+        }
+
+        rules = crate::shadowclaw::inviterules::set_invite_rules(
+            pool,
+            guild_id,
+            rules,
+            ctx.data().intents,
+        )
+        .await?;
+    }
+
+    let description = if rules.is_empty() {
+        "Anyone can get an invite to this server".to_string()
+    } else {
+        rules
+            .iter()
+            .map(|r| match r {
+                InviteRule::MinAccountAge { days } => {
+                    format!("- Discord account must be at least {} days old", days)
+                }
+                InviteRule::RecentVote { hours } => {
+                    format!(
+                        "- Must have voted for this server in the last {} hours",
+                        hours
+                    )
+                }
+                InviteRule::MaxListedServers { count } => format!(
+                    "- Must be in at most {} other servers on Infinity List",
+                    count
+                ),
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    };
+
+    ctx.send(
+        CreateReply::new().embed(
+            CreateEmbed::new()
+                .title("Invite Rules")
+                .description(description),
+        ),
+    )
+    .await?;
+
+    Ok(())
+}
+
+/// Stop Infinity List from handing out invites to your server, needs 'server.pause_invites' permissions
+#[poise::command(
+    prefix_command,
+    slash_command,
+    rename = "pause",
+    check = "_invites_pause_check"
+)]
+pub async fn invites_pause(
+    ctx: Context<'_>,
+    #[description = "How many minutes to pause invites for (default: until resumed)"]
+    #[min = 1]
+    minutes: Option<u32>,
+) -> Result<(), Error> {
+    let Some(guild_id) = ctx.guild_id() else {
+        return Err("This command can only be executed in a server".into());
+    };
+
+    let until = minutes.map(|m| chrono::Utc::now() + chrono::Duration::minutes(m.into()));
+
+    crate::shadowclaw::pause::pause_invites(&ctx.data().pool, guild_id, until).await?;
+
+    ctx.send(
+        CreateReply::new().embed(
+            CreateEmbed::new()
+                .title("Invites Paused")
+                .description(match until {
+                    Some(until) => format!(
+                        "Infinity List will not hand out invites to this server until <t:{}:f>",
+                        until.timestamp()
+                    ),
+                    None => "Infinity List will not hand out invites to this server until you run `/invites resume`".to_string(),
+                }),
+        ),
+    )
+    .await?;
+
+    Ok(())
+}
+
+/// Let Infinity List hand out invites to your server again, needs 'server.pause_invites' permissions
+#[poise::command(
+    prefix_command,
+    slash_command,
+    rename = "resume",
+    check = "_invites_pause_check"
+)]
+pub async fn invites_resume(ctx: Context<'_>) -> Result<(), Error> {
+    let Some(guild_id) = ctx.guild_id() else {
+        return Err("This command can only be executed in a server".into());
+    };
+
+    crate::shadowclaw::pause::resume_invites(&ctx.data().pool, guild_id).await?;
+
+    ctx.send(
+        CreateReply::new().embed(
+            CreateEmbed::new()
+                .title("Invites Resumed")
+                .description("Infinity List will hand out invites to this server again"),
+        ),
+    )
+    .await?;
+
+    Ok(())
+}
@@ -1,4 +1,9 @@
+pub mod blacklist;
 pub mod delete;
+pub mod growth;
+pub mod invites;
 pub mod leaderboard;
+pub mod settings;
 pub mod setup;
 pub mod update;
+pub mod whitelist;
@@ -0,0 +1,230 @@
+use crate::shadowclaw::perms::check_for_permission;
+use crate::{Context, Error};
+use poise::{
+    serenity_prelude::{CreateEmbed, GuildChannel},
+    CreateReply,
+};
+
+async fn _settings_check(ctx: Context<'_>) -> Result<bool, Error> {
+    check_for_permission(&ctx, "server.edit").await?;
+    Ok(true)
+}
+
+#[derive(poise::ChoiceParameter)]
+enum InviteMismatchAction {
+    #[name = "Do nothing"]
+    None,
+    #[name = "Alert the alert channel"]
+    Alert,
+    #[name = "Kick the member and alert the alert channel"]
+    Kick,
+}
+
+impl InviteMismatchAction {
+    fn as_str(&self) -> &'static str {
+        match self {
+            InviteMismatchAction::None => "none",
+            InviteMismatchAction::Alert => "alert",
+            InviteMismatchAction::Kick => "kick",
+        }
+    }
+}
+
+/// View or change your servers settings on Infinity List, needs 'server.edit' permissions
+#[poise::command(prefix_command, slash_command, check = "_settings_check")]
+pub async fn settings(
+    ctx: Context<'_>,
+    #[description = "Maximum number of invites to hand out per day (0 = unlimited)"]
+    daily_invite_quota: Option<u32>,
+    #[description = "What to do when someone joins using a per-user invite created for someone else"]
+    invite_mismatch_action: Option<InviteMismatchAction>,
+    #[description = "Channel to post alerts about your server to"] alert_channel: Option<
+        GuildChannel,
+    >,
+    #[description = "Refuse invites to users banned from this server (needs the Ban Members permission)"]
+    check_guild_bans: Option<bool>,
+    #[description = "Pause invites once this many members join through Infinity List in the raid window (0 = never)"]
+    raid_join_threshold: Option<u32>,
+    #[description = "The raid window in seconds"]
+    #[min = 10]
+    #[max = 86400]
+    raid_window_secs: Option<u32>,
+    #[description = "How many minutes to pause invites for when a raid is detected"]
+    #[min = 1]
+    #[max = 10080]
+    raid_pause_minutes: Option<u32>,
+    #[description = "Add members who become administrators to your team automatically"]
+    auto_promote_admins: Option<bool>,
+) -> Result<(), Error> {
+    let Some(guild_id) = ctx.guild_id() else {
+        return Err("This command can only be executed in a server".into());
+    };
+
+    let data = ctx.data();
+
+    if let Some(daily_invite_quota) = daily_invite_quota {
+        sqlx::query!(
+            "UPDATE servers SET daily_invite_quota = $2 WHERE server_id = $1",
+            guild_id.to_string(),
+            if daily_invite_quota == 0 {
+                None
+            } else {
+                Some(i32::try_from(daily_invite_quota)?)
+            }
+        )
+        .execute(&data.pool)
+        .await?;
+    }
+
+    if let Some(invite_mismatch_action) = invite_mismatch_action {
+        sqlx::query!(
+            "UPDATE servers SET invite_mismatch_action = $2 WHERE server_id = $1",
+            guild_id.to_string(),
+            invite_mismatch_action.as_str()
+        )
+        .execute(&data.pool)
+        .await?;
+    }
+
+    if let Some(alert_channel) = alert_channel {
+        if alert_channel.guild_id != guild_id {
+            return Err("The alert channel must be in this server".into());
+        }
+
+        sqlx::query!(
+            "UPDATE servers SET alert_channel = $2 WHERE server_id = $1",
+            guild_id.to_string(),
+            alert_channel.id.to_string()
+        )
+        .execute(&data.pool)
+        .await?;
+    }
+
+    if let Some(check_guild_bans) = check_guild_bans {
+        if check_guild_bans {
+            // Make sure the ban list can actually be read before turning this on
+            if let Err(e) = ctx.http().get_ban(guild_id, ctx.author().id).await {
+                return Err(format!(
+                    "Infinity List needs the Ban Members permission to check bans: {}",
+                    e
+                )
+                .into());
+            }
+        }
+
+        sqlx::query!(
+            "UPDATE servers SET check_guild_bans = $2 WHERE server_id = $1",
+            guild_id.to_string(),
+            check_guild_bans
+        )
+        .execute(&data.pool)
+        .await?;
+    }
+
+    if let Some(raid_join_threshold) = raid_join_threshold {
+        sqlx::query!(
+            "UPDATE servers SET raid_join_threshold = $2 WHERE server_id = $1",
+            guild_id.to_string(),
+            if raid_join_threshold == 0 {
+                None
+            } else {
+                Some(i32::try_from(raid_join_threshold)?)
+            }
+        )
+        .execute(&data.pool)
+        .await?;
+    }
+
+    if let Some(raid_window_secs) = raid_window_secs {
+        sqlx::query!(
+            "UPDATE servers SET raid_window_secs = $2 WHERE server_id = $1",
+            guild_id.to_string(),
+            i32::try_from(raid_window_secs)?
+        )
+        .execute(&data.pool)
+        .await?;
+    }
+
+    if let Some(raid_pause_minutes) = raid_pause_minutes {
+        sqlx::query!(
+            "UPDATE servers SET raid_pause_minutes = $2 WHERE server_id = $1",
+            guild_id.to_string(),
+            i32::try_from(raid_pause_minutes)?
+        )
+        .execute(&data.pool)
+        .await?;
+    }
+
+    if let Some(auto_promote_admins) = auto_promote_admins {
+        sqlx::query!(
+            "UPDATE servers SET auto_promote_admins = $2 WHERE server_id = $1",
+            guild_id.to_string(),
+            auto_promote_admins
+        )
+        .execute(&data.pool)
+        .await?;
+    }
+
+    let row = sqlx::query!(
+        "SELECT daily_invite_quota, invite_mismatch_action, alert_channel, check_guild_bans, raid_join_threshold, raid_window_secs, raid_pause_minutes, auto_promote_admins, invite_health, invite_health_message FROM servers WHERE server_id = $1",
+        guild_id.to_string()
+    )
+    .fetch_one(&data.pool)
+    .await?;
+
+    ctx.send(
+        CreateReply::new().embed(
+            CreateEmbed::new()
+                .title("Server Settings")
+                .field(
+                    "Daily Invite Quota",
+                    match row.daily_invite_quota {
+                        Some(quota) => quota.to_string(),
+                        None => "Unlimited".to_string(),
+                    },
+                    true,
+                )
+                .field("Invite Mismatch Action", row.invite_mismatch_action, true)
+                .field(
+                    "Alert Channel",
+                    match row.alert_channel {
+                        Some(channel) => format!("<#{}>", channel),
+                        None => "None".to_string(),
+                    },
+                    true,
+                )
+                .field(
+                    "Check Guild Bans",
+                    if row.check_guild_bans { "Yes" } else { "No" },
+                    true,
+                )
+                .field(
+                    "Auto Promote Admins",
+                    if row.auto_promote_admins { "Yes" } else { "No" },
+                    true,
+                )
+                .field(
+                    "Raid Pause",
+                    match row.raid_join_threshold {
+                        Some(threshold) => format!(
+                            "Pause for {} minutes after {} joins within {} seconds",
+                            row.raid_pause_minutes, threshold, row.raid_window_secs
+                        ),
+                        None => "Disabled".to_string(),
+                    },
+                    false,
+                )
+                .field(
+                    "Invite Health",
+                    match row.invite_health_message {
+                        Some(message) => format!("{}: {}", row.invite_health, message),
+                        None => row.invite_health,
+                    },
+                    false,
+                ),
+        ),
+    )
+    .await?;
+
+    Ok(())
+}
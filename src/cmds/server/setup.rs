@@ -379,7 +379,7 @@ Notes:
         i32::try_from(guild_stats.online_members)?,
         &inputs[1].to_string(),
         &inputs[2].to_string(),
-        invite,
+        invite.to_stored()?,
         vanity_tag.itag,
         serde_json::Value::Array(vec![]),
         guild_stats.nsfw
@@ -1,3 +1,5 @@
+use crate::shadowclaw::inviteschedule::{self, InviteSchedule};
+use crate::shadowclaw::invitespec::InviteSpec;
 use crate::shadowclaw::perms::check_for_permission;
 use crate::{Context, Error};
 use poise::{
@@ -21,6 +23,8 @@ enum UpdatePane {
     BasicInfo,
     #[name = "Server Invite"]
     Invite,
+    #[name = "Invite Schedule"]
+    InviteSchedule,
 }
 
 /// Update your server information on Infinity List, needs 'server.edit' permissions
@@ -165,17 +169,177 @@ pub async fn update(
         UpdatePane::Invite => {
             let invite = crate::shadowclaw::invite::setup_invite_view(&ctx).await?;
 
-            log::info!("Invite: {}", invite);
+            log::info!("Invite: {:?}", invite);
 
-            // Save to the database
-            sqlx::query!(
-                "UPDATE servers SET invite = $2 WHERE server_id = $1",
+            // Save to the database, subqueries in RETURNING see the row as it was before the update
+            let previous = sqlx::query!(
+                "UPDATE servers SET invite = $2 WHERE server_id = $1 RETURNING (SELECT invite FROM servers WHERE server_id = $1) AS previous",
                 guild_id.to_string(),
-                invite
+                invite.to_stored()?
             )
-            .execute(&ctx.data().pool)
+            .fetch_optional(&ctx.data().pool)
             .await?;
 
+            // Invites created under the previous invite mode should no longer be usable
+            let previous_mode = previous
+                .and_then(|p| p.previous)
+                .and_then(|p| InviteSpec::from_stored(&p).ok())
+                .map(|p| p.mode());
+
+            if previous_mode.is_some_and(|m| m != invite.mode()) {
+                let revoked = crate::shadowclaw::invitestore::revoke_server_invites(
+                    &ctx.serenity_context().http,
+                    &ctx.data().pool,
+                    guild_id,
+                    "Server switched invite mode",
+                )
+                .await?;
+
+                log::info!(
+                    "Revoked {} invites of {} after a mode switch",
+                    revoked,
+                    guild_id
+                );
+            }
+
+            ctx.send(
+                CreateReply::new().embed(
+                    CreateEmbed::new()
+                        .title("All Done!")
+                        .description("All done :white_check_mark:"),
+                ),
+            )
+            .await?;
+        }
+        UpdatePane::InviteSchedule => {
+            let current = inviteschedule::get_invite_schedule(&ctx.data().pool, guild_id).await?;
+
+            let builder = CreateReply::default()
+                .embed(
+                    CreateEmbed::new()
+                        .title("Update Invite Schedule")
+                        .description(format!(
+                            "Invites to your server will only be handed out during the windows of this schedule, one per line such as `mon 09:00-17:00`. Leave the windows empty to hand out invites at all times.\n\n**Current Schedule**\n{}",
+                            match &current {
+                                Some(schedule) => format!(
+                                    "Timezone: {}\n```\n{}\n```",
+                                    schedule.timezone,
+                                    schedule.format_windows()
+                                ),
+                                None => "Always open".to_string(),
+                            }
+                        )),
+                )
+                .components(vec![CreateActionRow::Buttons(vec![
+                    CreateButton::new("next")
+                        .label("Next")
+                        .style(ButtonStyle::Primary),
+                    CreateButton::new("cancel")
+                        .label("Cancel")
+                        .style(ButtonStyle::Danger),
+                ])]);
+
+            let msg = ctx.send(builder).await?.into_message().await?;
+
+            let Some(m) = msg
+                .await_component_interaction(ctx.serenity_context().shard.clone())
+                .author_id(ctx.author().id)
+                .timeout(Duration::from_secs(360))
+                .await
+            else {
+                ctx.send(
+                    CreateReply::new()
+                        .embed(
+                            CreateEmbed::new()
+                                .title("Update Timed Out")
+                                .description("Please rerun `/update`!"),
+                        )
+                        .ephemeral(true),
+                )
+                .await?;
+
+                return Ok(());
+            };
+
+            if m.data.custom_id == "cancel" {
+                m.create_response(
+                    ctx.http(),
+                    CreateInteractionResponse::UpdateMessage(
+                        CreateInteractionResponseMessage::default().components(vec![]),
+                    ),
+                )
+                .await?;
+
+                return Ok(());
+            }
+
+            let qm = CreateQuickModal::new("Invite Schedule")
+                .field(
+                    CreateInputText::new(InputTextStyle::Short, "Timezone", "timezone")
+                        .placeholder("An IANA timezone such as Europe/Berlin")
+                        .value(
+                            current
+                                .as_ref()
+                                .map(|s| s.timezone.clone())
+                                .unwrap_or_else(|| "UTC".to_string()),
+                        )
+                        .max_length(64)
+                        .required(true),
+                )
+                .field(
+                    CreateInputText::new(InputTextStyle::Paragraph, "Windows", "windows")
+                        .placeholder("mon 09:00-17:00\ntue 09:00-17:00")
+                        .value(
+                            current
+                                .as_ref()
+                                .map(|s| s.format_windows())
+                                .unwrap_or_default(),
+                        )
+                        .max_length(2000)
+                        .required(false),
+                );
+
+            let Some(resp) = m.quick_modal(ctx.serenity_context(), qm).await? else {
+                ctx.send(
+                    CreateReply::new()
+                        .embed(
+                            CreateEmbed::new()
+                                .title("Modal Timed Out")
+                                .description("Please rerun `/update`!"),
+                        )
+                        .ephemeral(true),
+                )
+                .await?;
+
+                return Ok(());
+            };
+
+            resp.interaction
+                .create_response(
+                    ctx.http(),
+                    CreateInteractionResponse::Message(
+                        CreateInteractionResponseMessage::default().embed(
+                            CreateEmbed::new()
+                                .title("Updating invite schedule...")
+                                .description("This may take a second, please wait..."),
+                        ),
+                    ),
+                )
+                .await?;
+
+            let windows = InviteSchedule::parse_windows(&resp.inputs[1])?;
+
+            let schedule = if windows.is_empty() {
+                None
+            } else {
+                Some(InviteSchedule {
+                    timezone: resp.inputs[0].trim().to_string(),
+                    windows,
+                })
+            };
+
+            inviteschedule::set_invite_schedule(&ctx.data().pool, guild_id, schedule).await?;
+
             ctx.send(
                 CreateReply::new().embed(
                     CreateEmbed::new()
@@ -0,0 +1,130 @@
+use crate::shadowclaw::perms::check_for_permission;
+use crate::{Context, Error};
+use poise::{serenity_prelude::CreateEmbed, CreateReply};
+use serenity::all::Mentionable;
+
+async fn _whitelist_check(ctx: Context<'_>) -> Result<bool, Error> {
+    check_for_permission(&ctx, "server.whitelist").await?;
+    Ok(true)
+}
+
+/// Manage the users who can get an invite to your server in whitelist mode, needs 'server.whitelist' permissions
+#[poise::command(
+    prefix_command,
+    slash_command,
+    subcommands("whitelist_add", "whitelist_remove", "whitelist_list"),
+    subcommand_required
+)]
+pub async fn whitelist(_ctx: Context<'_>) -> Result<(), Error> {
+    Ok(())
+}
+
+/// Add a user to the whitelist
+#[poise::command(
+    prefix_command,
+    slash_command,
+    rename = "add",
+    check = "_whitelist_check"
+)]
+pub async fn whitelist_add(
+    ctx: Context<'_>,
+    #[description = "The user to whitelist"] user: serenity::all::User,
+) -> Result<(), Error> {
+    let Some(guild_id) = ctx.guild_id() else {
+        return Err("This command can only be executed in a server".into());
+    };
+
+    crate::shadowclaw::whitelist::add_to_whitelist(&ctx.data().pool, guild_id, user.id).await?;
+
+    ctx.send(
+        CreateReply::new().embed(
+            CreateEmbed::new()
+                .title("Whitelist Updated")
+                .description(format!("{} has been whitelisted", user.id.mention())),
+        ),
+    )
+    .await?;
+
+    Ok(())
+}
+
+/// Remove a user from the whitelist
+#[poise::command(
+    prefix_command,
+    slash_command,
+    rename = "remove",
+    check = "_whitelist_check"
+)]
+pub async fn whitelist_remove(
+    ctx: Context<'_>,
+    #[description = "The user to remove from the whitelist"] user: serenity::all::User,
+) -> Result<(), Error> {
+    let Some(guild_id) = ctx.guild_id() else {
+        return Err("This command can only be executed in a server".into());
+    };
+
+    crate::shadowclaw::whitelist::remove_from_whitelist(&ctx.data().pool, guild_id, user.id)
+        .await?;
+
+    ctx.send(
+        CreateReply::new().embed(CreateEmbed::new().title("Whitelist Updated").description(
+            format!("{} has been removed from the whitelist", user.id.mention()),
+        )),
+    )
+    .await?;
+
+    Ok(())
+}
+
+/// List all whitelisted users
+#[poise::command(
+    prefix_command,
+    slash_command,
+    rename = "list",
+    check = "_whitelist_check"
+)]
+pub async fn whitelist_list(ctx: Context<'_>) -> Result<(), Error> {
+    let Some(guild_id) = ctx.guild_id() else {
+        return Err("This command can only be executed in a server".into());
+    };
+
+    let whitelist = crate::shadowclaw::whitelist::get_whitelist(&ctx.data().pool, guild_id).await?;
+
+    if whitelist.is_empty() {
+        ctx.say("No users are whitelisted").await?;
+        return Ok(());
+    }
+
+    let mut response = String::new();
+
+    for user_id in whitelist {
+        let next_str = format!("- <@{}> ({})\n", user_id, user_id);
+
+        if response.len() + next_str.len() > 4000 {
+            ctx.send(
+                CreateReply::new().embed(
+                    CreateEmbed::new()
+                        .title("Whitelisted Users")
+                        .description(response.clone()),
+                ),
+            )
+            .await?;
+            response.clear();
+        }
+
+        response.push_str(&next_str);
+    }
+
+    if !response.is_empty() {
+        ctx.send(
+            CreateReply::new().embed(
+                CreateEmbed::new()
+                    .title("Whitelisted Users")
+                    .description(response),
+            ),
+        )
+        .await?;
+    }
+
+    Ok(())
+}
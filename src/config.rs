@@ -51,6 +51,95 @@ impl Default for Servers {
     }
 }
 
+/// A token bucket, allowing ``capacity`` requests every ``per_secs`` seconds
+#[derive(Serialize, Deserialize, Clone, Copy)]
+pub struct Ratelimit {
+    pub capacity: u32,
+    pub per_secs: u64,
+}
+
+/// Ratelimits for creating invites through sorbet
+#[derive(Serialize, Deserialize)]
+pub struct InviteRatelimits {
+    /// Per logged-in user, across all servers
+    pub per_user: Ratelimit,
+    /// Per server, across all users (including anonymous ones)
+    pub per_guild: Ratelimit,
+    /// Across all users and servers
+    pub global: Ratelimit,
+}
+
+impl Default for InviteRatelimits {
+    fn default() -> Self {
+        Self {
+            per_user: Ratelimit {
+                capacity: 5,
+                per_secs: 60,
+            },
+            per_guild: Ratelimit {
+                capacity: 60,
+                per_secs: 60,
+            },
+            global: Ratelimit {
+                capacity: 300,
+                per_secs: 60,
+            },
+        }
+    }
+}
+
+impl InviteRatelimits {
+    /// Rejects buckets that would never refill, as they can't compute a retry time
+    fn validate(&self) -> Result<(), Error> {
+        for (name, limit) in [
+            ("per_user", &self.per_user),
+            ("per_guild", &self.per_guild),
+            ("global", &self.global),
+        ] {
+            if limit.capacity == 0 || limit.per_secs == 0 {
+                return Err(format!(
+                    "invite_ratelimits.{}: capacity and per_secs must be greater than 0",
+                    name
+                )
+                .into());
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Which expiring invites are accepted as a servers fixed invite URL
+#[derive(Serialize, Deserialize, Clone, Copy, Default)]
+#[serde(tag = "type")]
+pub enum InviteExpiryPolicy {
+    /// Only permanent invites are accepted
+    #[default]
+    Permanent,
+    /// Invites that expire at least ``days`` days from now are accepted as well
+    MinDays { days: i64 },
+    /// Any invite is accepted
+    Any,
+}
+
+/// When the per-user invites Infinity List creates are revoked by the invite cleanup task
+#[derive(Serialize, Deserialize)]
+pub struct InviteCleanup {
+    /// Revoke invites once all of their uses have been used up
+    pub revoke_used: bool,
+    /// Revoke invites this many days after they were created, even if they never expire (0 = never)
+    pub max_lifetime_days: u32,
+}
+
+impl Default for InviteCleanup {
+    fn default() -> Self {
+        Self {
+            revoke_used: true,
+            max_lifetime_days: 0,
+        }
+    }
+}
+
 #[derive(Serialize, Deserialize)]
 pub struct Config {
     pub database_url: String,
@@ -62,6 +151,22 @@ pub struct Config {
     pub proxy_url: String,
     pub cdn_main_scope_path: String,
     pub server_port: Differs<u16>,
+    #[serde(default)]
+    pub invite_ratelimits: InviteRatelimits,
+    /// Hosts (such as link shorteners) whose redirects may be followed when resolving an invite URL
+    #[serde(default)]
+    pub invite_redirect_hosts: Vec<String>,
+    #[serde(default)]
+    pub invite_expiry_policy: InviteExpiryPolicy,
+    #[serde(default)]
+    pub invite_cleanup: InviteCleanup,
+    /// How many hours a server can be missing Infinity List before it is made private
+    #[serde(default = "default_bot_missing_grace_hours")]
+    pub bot_missing_grace_hours: u32,
+}
+
+fn default_bot_missing_grace_hours() -> u32 {
+    72
 }
 
 impl Default for Config {
@@ -88,6 +193,11 @@ impl Default for Config {
             },
             proxy_url: String::from("http://127.0.0.1:3219"),
             cdn_main_scope_path: String::from("/silverpelt/cdn/ibl"),
+            invite_ratelimits: InviteRatelimits::default(),
+            invite_redirect_hosts: Vec::new(),
+            invite_expiry_policy: InviteExpiryPolicy::default(),
+            invite_cleanup: InviteCleanup::default(),
+            bot_missing_grace_hours: default_bot_missing_grace_hours(),
         }
     }
 }
@@ -113,6 +223,8 @@ impl Config {
                 // Parse config.yaml
                 let cfg: Config = serde_yaml::from_reader(file)?;
 
+                cfg.invite_ratelimits.validate()?;
+
                 // Return config
                 Ok(cfg)
             }
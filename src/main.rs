@@ -91,10 +91,39 @@ async fn event_listener<'a>(
     match event {
         FullEvent::InteractionCreate { interaction } => {
             info!("Interaction received: {:?}", interaction.id());
+
+            if let serenity::all::Interaction::Component(component) = interaction {
+                if component.data.custom_id.starts_with("appl:") {
+                    crate::shadowclaw::applications::handle_review_interaction(
+                        ctx.serenity_context,
+                        &ctx.user_data().pool,
+                        component,
+                    )
+                    .await?;
+                }
+            }
         }
         FullEvent::Ready { data_about_bot } => {
             info!("{} is ready!", data_about_bot.user.name);
 
+            // The cache holds every guild the bot is in (as unavailable guilds) by now, so servers that removed the
+            // bot while it was offline can be caught here. Staging runs in different guilds, so skip it there
+            if *crate::config::CURRENT_ENV != "staging" {
+                match crate::shadowclaw::botmissing::mark_missing_guilds(
+                    &ctx.serenity_context.http,
+                    &ctx.user_data().pool,
+                    &ctx.serenity_context.cache.guilds(),
+                )
+                .await
+                {
+                    Ok(0) => {}
+                    Ok(missing) => info!("{} listed servers are missing Infinity List", missing),
+                    Err(e) => {
+                        log::error!("Failed to check for servers missing Infinity List: {}", e)
+                    }
+                }
+            }
+
             #[allow(clippy::collapsible_if)]
             if ctx.serenity_context.shard_id.0 == 0 {
                 if !CONNECT_STATE.read().await.has_started_bgtasks {
@@ -109,7 +138,140 @@ async fn event_listener<'a>(
                 }
             }
         }
-        FullEvent::GuildMemberUpdate { new, .. } => {
+        FullEvent::GuildCreate { guild, .. } => {
+            if let Err(e) = crate::shadowclaw::botmissing::restore_bot(
+                &ctx.serenity_context.http,
+                &ctx.user_data().pool,
+                guild.id,
+            )
+            .await
+            {
+                log::error!("Failed to restore bot status of guild {}: {}", guild.id, e);
+            }
+
+            if let Err(e) = crate::shadowclaw::tracker::snapshot_guild(
+                &ctx.serenity_context.http,
+                &ctx.user_data().pool,
+                guild.id,
+            )
+            .await
+            {
+                log::warn!("Failed to snapshot invites of guild {}: {}", guild.id, e);
+            }
+        }
+        FullEvent::GuildDelete { incomplete, .. } => {
+            // Unavailable guilds are an outage, not a removal
+            if !incomplete.unavailable {
+                crate::shadowclaw::botmissing::mark_bot_missing(
+                    &ctx.serenity_context.http,
+                    &ctx.user_data().pool,
+                    incomplete.id,
+                )
+                .await?;
+            }
+        }
+        FullEvent::GuildUpdate {
+            old_data_if_available,
+            new_data,
+        } => {
+            if let Err(e) = crate::shadowclaw::vanity::sync_vanity(
+                &ctx.serenity_context.http,
+                &ctx.user_data().pool,
+                new_data.id,
+                new_data.vanity_url_code.as_deref(),
+            )
+            .await
+            {
+                log::error!("Failed to sync vanity of guild {}: {}", new_data.id, e);
+            }
+
+            // Most guild updates don't touch the images, so skip downloading them if their hashes are unchanged
+            if old_data_if_available.as_ref().is_some_and(|old| {
+                old.icon == new_data.icon
+                    && old.banner == new_data.banner
+                    && old.splash == new_data.splash
+            }) {
+                return Ok(());
+            }
+
+            crate::shadowclaw::assets::sync_guild_assets(&ctx.user_data().pool, new_data).await?;
+        }
+        FullEvent::GuildMemberAddition { new_member } => {
+            if new_member.user.bot() {
+                return Ok(());
+            }
+
+            match crate::shadowclaw::tracker::attribute_join(
+                &ctx.serenity_context.http,
+                &ctx.user_data().pool,
+                new_member.guild_id,
+                new_member.user.id,
+            )
+            .await
+            {
+                Ok(Some(attribution)) => {
+                    info!(
+                        "Member {} joined {} using Infinity List invite {} ({})",
+                        new_member.user.id,
+                        new_member.guild_id,
+                        attribution.code,
+                        attribution.source
+                    );
+
+                    match crate::shadowclaw::pause::check_raid(
+                        &ctx.serenity_context.http,
+                        &ctx.user_data().pool,
+                        new_member.guild_id,
+                    )
+                    .await
+                    {
+                        Ok(true) => {
+                            log::warn!(
+                                "Paused invites to {} due to a spike in joins",
+                                new_member.guild_id
+                            );
+                        }
+                        Ok(false) => {}
+                        Err(e) => {
+                            log::error!(
+                                "Failed to check for raids in {}: {}",
+                                new_member.guild_id,
+                                e
+                            );
+                        }
+                    }
+
+                    if attribution.mismatch {
+                        crate::shadowclaw::tracker::handle_invite_mismatch(
+                            &ctx.serenity_context.http,
+                            &ctx.user_data().pool,
+                            new_member.guild_id,
+                            new_member.user.id,
+                            &attribution,
+                        )
+                        .await?;
+                    }
+                }
+                Ok(None) => {}
+                Err(e) => {
+                    log::warn!(
+                        "Failed to attribute join of {} to {}: {}",
+                        new_member.user.id,
+                        new_member.guild_id,
+                        e
+                    );
+                }
+            }
+        }
+        FullEvent::InviteDelete { data } => {
+            crate::shadowclaw::invitestore::mark_invite_deleted(&ctx.user_data().pool, &data.code)
+                .await?;
+        }
+        FullEvent::GuildMemberUpdate {
+            old_if_available,
+            new,
+            ..
+        } => {
             let Some(member) = new else {
                 return Err("GuildMemberUpdate: Member not found".into());
             };
@@ -122,6 +284,13 @@ async fn event_listener<'a>(
 
             let permissions = member.permissions(&ctx.serenity_context.cache)?;
 
+            // Only promote members who just became an administrator. Without the old member, there is no telling
+            // whether they were an administrator all along (and possibly removed from the team on purpose)
+            let became_admin = old_if_available
+                .as_ref()
+                .and_then(|old| old.permissions(&ctx.serenity_context.cache).ok())
+                .is_some_and(|p| !p.administrator());
+
             if !permissions.administrator() {
                 // Delete them if service is infernoplex
                 let res = sqlx::query!(
@@ -144,6 +313,17 @@ async fn event_listener<'a>(
                 )
                 .execute(pool)
                 .await?;
+
+                crate::shadowclaw::team::forget_promotion(pool, member.guild_id, member.user.id)
+                    .await?;
+            } else if became_admin
+                && crate::shadowclaw::team::promote_admin(pool, member.guild_id, member.user.id)
+                    .await?
+            {
+                info!(
+                    "Added new administrator {} of {} to its team",
+                    member.user.id, member.guild_id
+                );
             }
         }
         FullEvent::GuildMemberRemoval { guild_id, user, .. } => {
@@ -170,6 +350,8 @@ async fn event_listener<'a>(
             )
             .execute(pool)
             .await?;
+
+            crate::shadowclaw::team::forget_promotion(pool, *guild_id, user.id).await?;
         }
         _ => {}
     }
@@ -247,6 +429,11 @@ async fn main() {
             cmds::server::update::update(),
             cmds::server::delete::delete(),
             cmds::server::leaderboard::leaderboard(),
+            cmds::server::growth::growth(),
+            cmds::server::whitelist::whitelist(),
+            cmds::server::blacklist::blacklist(),
+            cmds::server::settings::settings(),
+            cmds::server::invites::invites(),
         ],
         // This code is run before every command
         pre_command: |ctx| {
@@ -0,0 +1,329 @@
+use super::invite::{CreateInviteForUserError, CreateInviteForUserResult};
+use super::invitespec::ApplicationInvite;
+use serenity::all::{
+    ButtonStyle, ComponentInteraction, CreateActionRow, CreateButton, CreateEmbed,
+    CreateInteractionResponse, CreateInteractionResponseFollowup, CreateMessage,
+    EditInteractionResponse, GuildId, Mentionable, UserId,
+};
+
+/// The maximum length of an answer to a join application question
+///
+/// Discord caps embeds at 6000 characters, so all questions and answers of an application plus the rest of the review
+/// embed (including the status added once it is reviewed) must fit in that
+pub const MAX_APPLICATION_ANSWER_LENGTH: usize = 850;
+
+/// Files a join application for a user and posts it to the review channel of the server
+pub async fn submit_application(
+    cache_http: &botox::cache::CacheHttpImpl,
+    pool: &sqlx::PgPool,
+    guild_id: GuildId,
+    user_id: UserId,
+    application: &ApplicationInvite,
+    answers: Option<Vec<String>>,
+) -> Result<CreateInviteForUserResult, CreateInviteForUserError> {
+    let Some(answers) = answers else {
+        return Err(CreateInviteForUserError::ApplicationAnswersRequired {
+            questions: application.questions.clone(),
+        });
+    };
+
+    if answers.len() != application.questions.len() {
+        return Err(CreateInviteForUserError::ApplicationAnswersRequired {
+            questions: application.questions.clone(),
+        });
+    }
+
+    for answer in &answers {
+        if answer.trim().is_empty() || answer.len() > MAX_APPLICATION_ANSWER_LENGTH {
+            return Err(CreateInviteForUserError::Generic {
+                message: format!(
+                    "Answers must be between 1 and {} characters long",
+                    MAX_APPLICATION_ANSWER_LENGTH
+                ),
+            });
+        }
+    }
+
+    let pending = sqlx::query!(
+        "SELECT COUNT(*) FROM server_applications WHERE server_id = $1 AND user_id = $2 AND state = 'pending'",
+        guild_id.to_string(),
+        user_id.to_string()
+    )
+    .fetch_one(pool)
+    .await
+    .map_err(|e| {
+        log::error!("Failed to fetch pending applications: {}", e);
+        CreateInviteForUserError::Generic {
+            message: format!("Failed to fetch pending applications: {}", e),
+        }
+    })?;
+
+    if pending.count.unwrap_or(0) > 0 {
+        return Err(CreateInviteForUserError::ApplicationAlreadyPending {});
+    }
+
+    let rec = sqlx::query!(
+        "INSERT INTO server_applications (server_id, user_id, questions, answers) VALUES ($1, $2, $3, $4) RETURNING id",
+        guild_id.to_string(),
+        user_id.to_string(),
+        &application.questions,
+        &answers
+    )
+    .fetch_one(pool)
+    .await
+    .map_err(|e| {
+        log::error!("Failed to create application: {}", e);
+        CreateInviteForUserError::Generic {
+            message: format!("Failed to create application: {}", e),
+        }
+    })?;
+
+    let mut embed = CreateEmbed::new()
+        .title("New Join Application")
+        .description(format!(
+            "{} ({}) wants to join this server",
+            user_id.mention(),
+            user_id
+        ))
+        .footer(serenity::all::CreateEmbedFooter::new(format!(
+            "Application ID: {}",
+            rec.id
+        )));
+
+    for (question, answer) in application.questions.iter().zip(answers.iter()) {
+        embed = embed.field(question, answer, false);
+    }
+
+    let posted = application
+        .review_channel_id
+        .send_message(
+            &cache_http.http,
+            CreateMessage::new()
+                .embed(embed)
+                .components(vec![CreateActionRow::Buttons(vec![
+                    CreateButton::new(format!("appl:approve:{}", rec.id))
+                        .label("Approve")
+                        .style(ButtonStyle::Success),
+                    CreateButton::new(format!("appl:deny:{}", rec.id))
+                        .label("Deny")
+                        .style(ButtonStyle::Danger),
+                ])]),
+        )
+        .await;
+
+    if let Err(e) = posted {
+        log::error!("Failed to post application for review: {}", e);
+
+        // Don't leave behind a pending application that nobody can review
+        if let Err(e) = sqlx::query!("DELETE FROM server_applications WHERE id = $1", rec.id)
+            .execute(pool)
+            .await
+        {
+            log::error!("Failed to delete unposted application {}: {}", rec.id, e);
+        }
+
+        return Err(CreateInviteForUserError::Generic {
+            message: format!("Failed to post application for review: {}", e),
+        });
+    }
+
+    Ok(CreateInviteForUserResult::ApplicationSubmitted {
+        application_id: rec.id.to_string(),
+    })
+}
+
+/// Handles the approve/deny buttons on a join application posted by ``submit_application``
+pub async fn handle_review_interaction(
+    ctx: &serenity::all::Context,
+    pool: &sqlx::PgPool,
+    interaction: &ComponentInteraction,
+) -> Result<(), crate::Error> {
+    let splitted = interaction.data.custom_id.split(':').collect::<Vec<_>>();
+
+    if splitted.len() != 3 {
+        return Err("Invalid application interaction".into());
+    }
+
+    let approve = match splitted[1] {
+        "approve" => true,
+        "deny" => false,
+        _ => return Err("Invalid application action".into()),
+    };
+
+    let application_id = splitted[2].parse::<sqlx::types::Uuid>()?;
+
+    let Some(guild_id) = interaction.guild_id else {
+        return Err("Applications can only be reviewed in a server".into());
+    };
+
+    // Approving creates an invite, which can take longer than Discord waits for a response
+    interaction
+        .create_response(&ctx.http, CreateInteractionResponse::Acknowledge)
+        .await?;
+
+    if let Err(e) = crate::shadowclaw::perms::check_permission(
+        pool,
+        guild_id,
+        interaction.user.id,
+        "server.applications",
+    )
+    .await
+    {
+        return reply_ephemeral(ctx, interaction, &e.to_string()).await;
+    }
+
+    // Claim the application first, so concurrent reviews can't both act on it
+    let Some(application) = sqlx::query!(
+        "UPDATE server_applications SET state = $3, reviewed_by = $4, reviewed_at = NOW()
+        WHERE id = $1 AND server_id = $2 AND state = 'pending'
+        RETURNING user_id",
+        application_id,
+        guild_id.to_string(),
+        if approve { "approved" } else { "denied" },
+        interaction.user.id.to_string()
+    )
+    .fetch_optional(pool)
+    .await?
+    else {
+        let state = sqlx::query!(
+            "SELECT state FROM server_applications WHERE id = $1 AND server_id = $2",
+            application_id,
+            guild_id.to_string()
+        )
+        .fetch_optional(pool)
+        .await?;
+
+        return match state {
+            Some(application) => {
+                reply_ephemeral(
+                    ctx,
+                    interaction,
+                    &format!("This application has already been {}", application.state),
+                )
+                .await
+            }
+            None => reply_ephemeral(ctx, interaction, "This application no longer exists").await,
+        };
+    };
+
+    let applicant = application.user_id.parse::<UserId>()?;
+
+    let guild_name = ctx
+        .cache
+        .guild(guild_id)
+        .map(|g| g.name.to_string())
+        .unwrap_or_else(|| guild_id.to_string());
+
+    let result = if approve {
+        let cache_http = botox::cache::CacheHttpImpl::from_ctx(ctx);
+
+        let invite = match super::invite::create_invite_for_user(
+            &cache_http,
+            pool,
+            guild_id,
+            Some(applicant),
+            true,
+            None,
+        )
+        .await
+        {
+            Ok(CreateInviteForUserResult::Invite { url }) => url,
+            res => {
+                // Release the claim so the application can be reviewed again
+                sqlx::query!(
+                    "UPDATE server_applications SET state = 'pending', reviewed_by = NULL, reviewed_at = NULL WHERE id = $1",
+                    application_id
+                )
+                .execute(pool)
+                .await?;
+
+                return reply_ephemeral(
+                    ctx,
+                    interaction,
+                    &match res {
+                        Err(e) => format!("Could not create an invite for this applicant: {}", e),
+                        Ok(_) => "Could not create an invite for this applicant".to_string(),
+                    },
+                )
+                .await;
+            }
+        };
+
+        applicant
+            .direct_message(
+                ctx,
+                CreateMessage::new().embed(
+                    CreateEmbed::new()
+                        .title("Application Approved")
+                        .description(format!(
+                            "Your application to join **{}** has been approved! Use this invite to join: {}",
+                            guild_name, invite
+                        )),
+                ),
+            )
+            .await
+            .map(|_| ())
+    } else {
+        applicant
+            .direct_message(
+                ctx,
+                CreateMessage::new().embed(
+                    CreateEmbed::new()
+                        .title("Application Denied")
+                        .description(format!(
+                            "Unfortunately, your application to join **{}** has been denied",
+                            guild_name
+                        )),
+                ),
+            )
+            .await
+            .map(|_| ())
+    };
+
+    let mut status = format!(
+        "{} by {}",
+        if approve { "Approved" } else { "Denied" },
+        interaction.user.id.mention()
+    );
+
+    if let Err(e) = result {
+        log::warn!("Failed to DM applicant {}: {}", applicant, e);
+        status.push_str(" (the applicant could not be notified as their DMs are closed)");
+    }
+
+    let mut embed = match interaction.message.embeds.first() {
+        Some(embed) => CreateEmbed::from(embed.clone()),
+        None => CreateEmbed::new().title("Join Application"),
+    };
+
+    embed = embed.field("Status", status, false);
+
+    interaction
+        .edit_response(
+            &ctx.http,
+            EditInteractionResponse::new()
+                .embed(embed)
+                .components(vec![]),
+        )
+        .await?;
+
+    Ok(())
+}
+
+/// Sends an ephemeral reply to an acknowledged interaction
+async fn reply_ephemeral(
+    ctx: &serenity::all::Context,
+    interaction: &ComponentInteraction,
+    message: &str,
+) -> Result<(), crate::Error> {
+    interaction
+        .create_followup(
+            &ctx.http,
+            CreateInteractionResponseFollowup::new()
+                .content(message)
+                .ephemeral(true),
+        )
+        .await?;
+
+    Ok(())
+}
@@ -0,0 +1,183 @@
+use once_cell::sync::Lazy;
+use serenity::all::Guild;
+
+/// The image shown when a guild has no icon
+const DEFAULT_ICON_URL: &str = "https://cdn.discordapp.com/embed/avatars/0.png";
+
+/// The hash stored for guilds without an icon, which use the default icon
+const DEFAULT_ICON_HASH: &str = "default";
+
+/// A guild image mirrored to the CDN
+#[derive(Clone, Copy)]
+pub enum GuildAsset {
+    Icon,
+    Banner,
+    Splash,
+}
+
+impl GuildAsset {
+    fn kind(&self) -> &'static str {
+        match self {
+            GuildAsset::Icon => "icon",
+            GuildAsset::Banner => "banner",
+            GuildAsset::Splash => "splash",
+        }
+    }
+
+    /// Returns the CDN paths the asset is written to
+    ///
+    /// The server icon doubles as the avatar of the servers team
+    fn paths(&self, server_id: &str, team_id: &str) -> Vec<String> {
+        let cdn = &crate::config::CONFIG.cdn_main_scope_path;
+
+        match self {
+            GuildAsset::Icon => vec![
+                format!("{}/avatars/servers/{}.webp", cdn, server_id),
+                format!("{}/avatars/teams/{}.webp", cdn, team_id),
+            ],
+            GuildAsset::Banner => vec![format!("{}/banners/servers/{}.webp", cdn, server_id)],
+            GuildAsset::Splash => vec![format!("{}/splashes/servers/{}.webp", cdn, server_id)],
+        }
+    }
+}
+
+/// Shared HTTP client used to download guild images
+static CLIENT: Lazy<reqwest::Client> = Lazy::new(|| {
+    reqwest::Client::builder()
+        .timeout(std::time::Duration::from_secs(10))
+        .build()
+        .expect("Failed to build asset HTTP client")
+});
+
+/// Mirrors the icon, banner and splash of a listed guild to the CDN
+///
+/// Images are only downloaded if their Discord image hash changed since the last sync. Banners
+/// and splashes the guild no longer has are removed from the CDN
+pub async fn sync_guild_assets(pool: &sqlx::PgPool, guild: &Guild) -> Result<(), crate::Error> {
+    let guild_id = guild.id;
+
+    let Some(server) = sqlx::query!(
+        "SELECT team_owner FROM servers WHERE server_id = $1",
+        guild_id.to_string()
+    )
+    .fetch_optional(pool)
+    .await?
+    else {
+        return Ok(());
+    };
+
+    let server_id = guild_id.to_string();
+    let team_id = server.team_owner.to_string();
+
+    let icon = match (guild.icon.as_ref(), guild.icon_url()) {
+        (Some(hash), Some(url)) => (hash.to_string(), url),
+        _ => (DEFAULT_ICON_HASH.to_string(), DEFAULT_ICON_URL.to_string()),
+    };
+
+    for (asset, image) in [
+        (GuildAsset::Icon, Some(icon)),
+        (
+            GuildAsset::Banner,
+            guild
+                .banner
+                .as_ref()
+                .map(|h| h.to_string())
+                .zip(guild.banner_url()),
+        ),
+        (
+            GuildAsset::Splash,
+            guild
+                .splash
+                .as_ref()
+                .map(|h| h.to_string())
+                .zip(guild.splash_url()),
+        ),
+    ] {
+        if let Err(e) = sync_asset(pool, &server_id, &team_id, asset, image).await {
+            log::error!(
+                "Failed to sync {} of {} to the CDN: {}",
+                asset.kind(),
+                guild_id,
+                e
+            );
+        }
+    }
+
+    Ok(())
+}
+
+/// Syncs a single guild image given as its Discord image hash and URL
+async fn sync_asset(
+    pool: &sqlx::PgPool,
+    server_id: &str,
+    team_id: &str,
+    asset: GuildAsset,
+    image: Option<(String, String)>,
+) -> Result<(), crate::Error> {
+    let stored = sqlx::query!(
+        "SELECT hash FROM server_assets WHERE server_id = $1 AND kind = $2",
+        server_id,
+        asset.kind()
+    )
+    .fetch_optional(pool)
+    .await?;
+
+    let Some((hash, url)) = image else {
+        if stored.is_some() {
+            for path in asset.paths(server_id, team_id) {
+                if let Err(e) = std::fs::remove_file(&path) {
+                    log::warn!("Failed to remove {}: {}", path, e);
+                }
+            }
+
+            sqlx::query!(
+                "DELETE FROM server_assets WHERE server_id = $1 AND kind = $2",
+                server_id,
+                asset.kind()
+            )
+            .execute(pool)
+            .await?;
+        }
+
+        return Ok(());
+    };
+
+    if stored.is_some_and(|s| s.hash == hash) {
+        return Ok(());
+    }
+
+    let bytes = CLIENT
+        .get(&url)
+        .send()
+        .await?
+        .error_for_status()?
+        .bytes()
+        .await?
+        .to_vec();
+
+    let paths = asset.paths(server_id, team_id);
+
+    // cwebp/gif2webp are blocking
+    tokio::task::spawn_blocking(move || {
+        for path in paths {
+            super::webp::image_to_webp(&url, path, &bytes)?;
+        }
+
+        Ok::<_, crate::Error>(())
+    })
+    .await??;
+
+    sqlx::query!(
+        "INSERT INTO server_assets (server_id, kind, hash) VALUES ($1, $2, $3)
+        ON CONFLICT (server_id, kind) DO UPDATE SET hash = EXCLUDED.hash, updated_at = NOW()",
+        server_id,
+        asset.kind(),
+        hash
+    )
+    .execute(pool)
+    .await?;
+
+    log::info!("Synced {} of {} to the CDN", asset.kind(), server_id);
+
+    Ok(())
+}
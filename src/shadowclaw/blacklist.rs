@@ -0,0 +1,191 @@
+use serde::{Deserialize, Serialize};
+use serenity::all::{GuildId, UserId};
+use ts_rs::TS;
+use utoipa::ToSchema;
+
+/// The maximum length of the reason of a blacklist entry
+pub const MAX_BLACKLIST_REASON_LENGTH: usize = 512;
+
+/// A user on the invite blacklist of a server
+#[derive(Debug, Serialize, Deserialize, ToSchema, TS, Clone)]
+#[ts(export, export_to = ".generated/BlacklistEntry.ts")]
+pub struct BlacklistEntry {
+    pub user_id: String,
+    pub reason: Option<String>,
+    /// When the entry expires, None if it is permanent
+    #[schema(value_type = Option<String>)]
+    pub expires_at: Option<chrono::DateTime<chrono::Utc>>,
+    /// The team member who blacklisted the user, None for entries from before reasons were tracked
+    pub created_by: Option<String>,
+    #[schema(value_type = String)]
+    pub created_at: chrono::DateTime<chrono::Utc>,
+}
+
+/// Removes expired blacklist entries of a server
+async fn prune_expired(pool: &sqlx::PgPool, guild_id: GuildId) -> Result<(), crate::Error> {
+    sqlx::query!(
+        "DELETE FROM server_blacklist WHERE server_id = $1 AND expires_at IS NOT NULL AND expires_at <= NOW()",
+        guild_id.to_string()
+    )
+    .execute(pool)
+    .await?;
+
+    Ok(())
+}
+
+/// Returns the (unexpired) users on the invite blacklist of a server
+pub async fn get_blacklist(
+    pool: &sqlx::PgPool,
+    guild_id: GuildId,
+) -> Result<Vec<BlacklistEntry>, crate::Error> {
+    let count = sqlx::query!(
+        "SELECT COUNT(*) FROM servers WHERE server_id = $1",
+        guild_id.to_string()
+    )
+    .fetch_one(pool)
+    .await?;
+
+    if count.count.unwrap_or(0) == 0 {
+        return Err("This server is not on Infinity List!".into());
+    }
+
+    prune_expired(pool, guild_id).await?;
+
+    let entries = sqlx::query_as!(
+        BlacklistEntry,
+        "SELECT user_id, reason, expires_at, created_by, created_at FROM server_blacklist WHERE server_id = $1 ORDER BY created_at DESC",
+        guild_id.to_string()
+    )
+    .fetch_all(pool)
+    .await?;
+
+    Ok(entries)
+}
+
+/// Returns the unexpired users on the invite blacklist of a server whose ID starts with ``prefix``
+///
+/// Unlike ``get_blacklist``, this does not prune expired entries, so it is cheap enough for autocomplete
+pub async fn search_blacklist(
+    pool: &sqlx::PgPool,
+    guild_id: GuildId,
+    prefix: &str,
+    limit: i64,
+) -> Result<Vec<BlacklistEntry>, crate::Error> {
+    let entries = sqlx::query_as!(
+        BlacklistEntry,
+        "SELECT user_id, reason, expires_at, created_by, created_at FROM server_blacklist
+        WHERE server_id = $1 AND (expires_at IS NULL OR expires_at > NOW()) AND starts_with(user_id, $2)
+        ORDER BY created_at DESC LIMIT $3",
+        guild_id.to_string(),
+        prefix,
+        limit
+    )
+    .fetch_all(pool)
+    .await?;
+
+    Ok(entries)
+}
+
+/// Returns whether a user is on the (unexpired) invite blacklist of a server
+pub async fn is_blacklisted(
+    pool: &sqlx::PgPool,
+    guild_id: GuildId,
+    user_id: UserId,
+) -> Result<bool, crate::Error> {
+    let count = sqlx::query!(
+        "SELECT COUNT(*) FROM server_blacklist WHERE server_id = $1 AND user_id = $2 AND (expires_at IS NULL OR expires_at > NOW())",
+        guild_id.to_string(),
+        user_id.to_string()
+    )
+    .fetch_one(pool)
+    .await?;
+
+    Ok(count.count.unwrap_or(0) > 0)
+}
+
+/// Adds a user to the invite blacklist of a server, returning the new blacklist
+///
+/// Any outstanding invites created for the user are revoked
+pub async fn add_to_blacklist(
+    http: &serenity::all::Http,
+    pool: &sqlx::PgPool,
+    guild_id: GuildId,
+    user_id: UserId,
+    reason: Option<String>,
+    expires_at: Option<chrono::DateTime<chrono::Utc>>,
+    created_by: UserId,
+) -> Result<Vec<BlacklistEntry>, crate::Error> {
+    let blacklist = get_blacklist(pool, guild_id).await?;
+
+    if blacklist.iter().any(|e| e.user_id == user_id.to_string()) {
+        return Err("This user is already blacklisted".into());
+    }
+
+    if let Some(reason) = &reason {
+        if reason.len() > MAX_BLACKLIST_REASON_LENGTH {
+            return Err(format!(
+                "The reason can be at most {} characters long",
+                MAX_BLACKLIST_REASON_LENGTH
+            )
+            .into());
+        }
+    }
+
+    if let Some(expires_at) = expires_at {
+        if expires_at <= chrono::Utc::now() {
+            return Err("The expiry must be in the future".into());
+        }
+    }
+
+    sqlx::query!(
+        "INSERT INTO server_blacklist (server_id, user_id, reason, expires_at, created_by) VALUES ($1, $2, $3, $4, $5)",
+        guild_id.to_string(),
+        user_id.to_string(),
+        reason,
+        expires_at,
+        created_by.to_string()
+    )
+    .execute(pool)
+    .await?;
+
+    if let Err(e) = super::invitestore::revoke_user_invites(
+        http,
+        pool,
+        guild_id,
+        user_id,
+        "User was blacklisted from this server",
+    )
+    .await
+    {
+        log::error!(
+            "Failed to revoke invites of blacklisted user {}: {}",
+            user_id,
+            e
+        );
+    }
+
+    get_blacklist(pool, guild_id).await
+}
+
+/// Removes a user from the invite blacklist of a server, returning the new blacklist
+pub async fn remove_from_blacklist(
+    pool: &sqlx::PgPool,
+    guild_id: GuildId,
+    user_id: UserId,
+) -> Result<Vec<BlacklistEntry>, crate::Error> {
+    let blacklist = get_blacklist(pool, guild_id).await?;
+
+    if !blacklist.iter().any(|e| e.user_id == user_id.to_string()) {
+        return Err("This user is not blacklisted".into());
+    }
+
+    sqlx::query!(
+        "DELETE FROM server_blacklist WHERE server_id = $1 AND user_id = $2",
+        guild_id.to_string(),
+        user_id.to_string()
+    )
+    .execute(pool)
+    .await?;
+
+    get_blacklist(pool, guild_id).await
+}
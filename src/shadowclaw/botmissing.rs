@@ -0,0 +1,156 @@
+use serenity::all::{CreateEmbed, GuildId};
+
+/// The state servers are moved to once the bot has been missing for longer than the grace period
+const BOT_MISSING_STATE: &str = "private";
+
+/// Marks a server as missing the bot, notifying its team the first time
+pub async fn mark_bot_missing(
+    http: &serenity::all::Http,
+    pool: &sqlx::PgPool,
+    guild_id: GuildId,
+) -> Result<(), crate::Error> {
+    let res = sqlx::query!(
+        "UPDATE servers SET bot_missing_since = NOW() WHERE server_id = $1 AND bot_missing_since IS NULL",
+        guild_id.to_string()
+    )
+    .execute(pool)
+    .await?;
+
+    if res.rows_affected() == 0 {
+        return Ok(());
+    }
+
+    log::warn!("Infinity List was removed from listed server {}", guild_id);
+
+    // The alert channel is unreachable now, so DM the team instead
+    super::notify::dm_team(
+        http,
+        pool,
+        guild_id,
+        CreateEmbed::new()
+            .title("Infinity List Was Removed")
+            .description(format!(
+                "Infinity List is no longer in your server ({}), so visitors can no longer get an invite to it. Re-add Infinity List within {} hours or your server will be made private on Infinity List until it is re-added.",
+                guild_id,
+                crate::config::CONFIG.bot_missing_grace_hours
+            )),
+    )
+    .await
+}
+
+/// Marks every listed server that is not in ``guild_ids`` as missing the bot, returning how many were
+///
+/// Used on startup to catch servers that removed the bot while it was offline, ``guild_ids`` must be every guild the
+/// bot is in (including unavailable ones)
+pub async fn mark_missing_guilds(
+    http: &serenity::all::Http,
+    pool: &sqlx::PgPool,
+    guild_ids: &[GuildId],
+) -> Result<usize, crate::Error> {
+    // An empty guild list means the cache isn't ready, not that the bot was removed everywhere
+    if guild_ids.is_empty() {
+        return Ok(0);
+    }
+
+    let missing = sqlx::query!(
+        "SELECT server_id FROM servers WHERE bot_missing_since IS NULL AND NOT (server_id = ANY($1))",
+        &guild_ids.iter().map(|g| g.to_string()).collect::<Vec<_>>()
+    )
+    .fetch_all(pool)
+    .await?;
+
+    for server in &missing {
+        let guild_id = server.server_id.parse::<GuildId>()?;
+
+        if let Err(e) = mark_bot_missing(http, pool, guild_id).await {
+            log::error!(
+                "Failed to mark {} as missing Infinity List: {}",
+                guild_id,
+                e
+            );
+        }
+    }
+
+    Ok(missing.len())
+}
+
+/// Clears the bot missing status of a server, restoring its previous state if it was made private
+pub async fn restore_bot(
+    http: &serenity::all::Http,
+    pool: &sqlx::PgPool,
+    guild_id: GuildId,
+) -> Result<(), crate::Error> {
+    // Subqueries in RETURNING see the row as it was before the update. The previous state is only restored if the
+    // server is still in the state it was moved to, so changes made by staff in the meantime are kept
+    let Some(rec) = sqlx::query!(
+        "UPDATE servers SET bot_missing_since = NULL,
+        state = CASE WHEN state = $2 AND bot_missing_previous_state IS NOT NULL THEN bot_missing_previous_state ELSE state END,
+        bot_missing_previous_state = NULL
+        WHERE server_id = $1 AND bot_missing_since IS NOT NULL
+        RETURNING (SELECT bot_missing_previous_state FROM servers WHERE server_id = $1) AS previous_state, state",
+        guild_id.to_string(),
+        BOT_MISSING_STATE
+    )
+    .fetch_optional(pool)
+    .await?
+    else {
+        return Ok(());
+    };
+
+    log::info!("Infinity List was re-added to listed server {}", guild_id);
+
+    super::notify::notify_team(
+        http,
+        pool,
+        guild_id,
+        CreateEmbed::new()
+            .title("Welcome Back!")
+            .description(match rec.previous_state.filter(|p| *p == rec.state) {
+                Some(state) => format!(
+                    "Infinity List is back in your server, so your listing has been restored to {} and invites work again.",
+                    state
+                ),
+                None => "Infinity List is back in your server, so invites work again.".to_string(),
+            }),
+    )
+    .await
+}
+
+/// Makes servers that have been missing the bot for longer than the grace period private, returning how many were
+pub async fn hide_missing(
+    http: &serenity::all::Http,
+    pool: &sqlx::PgPool,
+) -> Result<usize, crate::Error> {
+    let hidden = sqlx::query!(
+        "UPDATE servers SET bot_missing_previous_state = state, state = $2
+        WHERE bot_missing_since < NOW() - make_interval(hours => $1) AND bot_missing_previous_state IS NULL
+        RETURNING server_id",
+        i32::try_from(crate::config::CONFIG.bot_missing_grace_hours)?,
+        BOT_MISSING_STATE
+    )
+    .fetch_all(pool)
+    .await?;
+
+    for server in &hidden {
+        let guild_id = server.server_id.parse::<GuildId>()?;
+
+        if let Err(e) = super::notify::dm_team(
+            http,
+            pool,
+            guild_id,
+            CreateEmbed::new()
+                .title("Server Made Private")
+                .description(format!(
+                    "Infinity List has been missing from your server ({}) for over {} hours, so it has been made private on Infinity List. Re-add Infinity List to restore your listing.",
+                    guild_id,
+                    crate::config::CONFIG.bot_missing_grace_hours
+                )),
+        )
+        .await
+        {
+            log::warn!("Failed to notify team of {} about being hidden: {}", guild_id, e);
+        }
+    }
+
+    Ok(hidden.len())
+}
@@ -1,11 +1,20 @@
 use std::time::Duration;
 
+use super::inviterules::InviteRule;
+use super::inviteschedule::InviteSchedule;
+use super::invitespec::{
+    ApplicationInvite, InviteSpec, PerUserInvite, MAX_FALLBACK_CHANNELS, MAX_INVITE_AGE,
+    MAX_INVITE_USES,
+};
+use crate::config::InviteExpiryPolicy;
 use crate::{Context, Error};
-use log::info;
 use poise::CreateReply;
 use serde::{Deserialize, Serialize};
 use serenity::{
-    all::{ButtonStyle, CreateActionRow, CreateButton, CreateEmbed, InputTextStyle},
+    all::{
+        ButtonStyle, CreateActionRow, CreateButton, CreateEmbed, CreateSelectMenu,
+        CreateSelectMenuKind, InputTextStyle,
+    },
     builder::{
         CreateInputText, CreateInteractionResponse, CreateInteractionResponseMessage,
         EditInteractionResponse,
@@ -20,8 +29,8 @@ use utoipa::ToSchema;
 
 /// Sets up the invite for a server
 ///
-/// This returns a string which is the invite selected by the user
-pub async fn setup_invite_view(ctx: &Context<'_>) -> Result<String, Error> {
+/// This returns the invite spec selected by the user
+pub async fn setup_invite_view(ctx: &Context<'_>) -> Result<InviteSpec, Error> {
     let Some(guild_id) = ctx.guild_id() else {
         return Err("This operation can only be performed in a server".into());
     };
@@ -34,7 +43,10 @@ pub async fn setup_invite_view(ctx: &Context<'_>) -> Result<String, Error> {
 Okay! Now, let's setup the invite for this server! To get started, choose which type of invite you would like
 
 - **Invite URL** - Use a (permanent) invite link of your choice
+- **Vanity URL** - Use this server's vanity URL, kept in sync automatically if it changes
 - **Per-User Invite** - Infinity List will create an invite for this server for each user
+- **Whitelist Only** - Infinity List will create an invite for each user, but only for users on this server's whitelist (see `/whitelist`)
+- **Applications** - Users must answer your questions and have their application approved by your team (needs `server.applications`) before they get an invite
 - **None** - This server will not be invitable. Useful, if you wish to use a whitelist form and manually send out invites
     "
             )
@@ -49,6 +61,12 @@ Okay! Now, let's setup the invite for this server! To get started, choose which
                         CreateButton::new("per_user")
                         .label("Per-User Invite")
                         .style(ButtonStyle::Primary),
+                        CreateButton::new("whitelist")
+                        .label("Whitelist Only")
+                        .style(ButtonStyle::Primary),
+                        CreateButton::new("application")
+                        .label("Applications")
+                        .style(ButtonStyle::Primary),
                         CreateButton::new("none")
                         .label("No Invites")
                         .style(ButtonStyle::Primary),
@@ -56,9 +74,9 @@ Okay! Now, let's setup the invite for this server! To get started, choose which
                 ),
                 CreateActionRow::Buttons(
                     vec![
-                        CreateButton::new("p1")
-                        .label("-")
-                        .disabled(true),
+                        CreateButton::new("vanity")
+                        .label("Vanity URL")
+                        .style(ButtonStyle::Primary),
                         CreateButton::new("cancel")
                         .label("Cancel")
                         .style(ButtonStyle::Danger),
@@ -95,8 +113,8 @@ Okay! Now, let's setup the invite for this server! To get started, choose which
                 // Ask for invite url now
                 let qm = CreateQuickModal::new("Invite URL Selection").field(
                     CreateInputText::new(InputTextStyle::Short, "Enter Invite URL", "invite_url")
-                        .placeholder("Please enter the Invite URL you wish to use!")
-                        .min_length(20)
+                        .placeholder("Please enter the Invite URL or code you wish to use!")
+                        .min_length(2)
                         .max_length(100),
                 );
 
@@ -122,23 +140,26 @@ Okay! Now, let's setup the invite for this server! To get started, choose which
                         .await?;
 
                     let cache_http = botox::cache::CacheHttpImpl::from_ctx(ctx.serenity_context());
-                    if let Err(e) = resolve_invite(&cache_http, guild_id, invite_url).await {
-                        resp.interaction
-                            .edit_response(
-                                ctx.http(),
-                                EditInteractionResponse::new().embed(
-                                    CreateEmbed::new()
-                                        .title("Error resolving invite")
-                                        .description(format!(
-                                            "This invite could not be resolved: {}",
-                                            e
-                                        )),
-                                ),
-                            )
-                            .await?;
+                    let invite_url = match resolve_invite(&cache_http, guild_id, invite_url).await {
+                        Ok(invite_url) => invite_url,
+                        Err(e) => {
+                            resp.interaction
+                                .edit_response(
+                                    ctx.http(),
+                                    EditInteractionResponse::new().embed(
+                                        CreateEmbed::new()
+                                            .title("Error resolving invite")
+                                            .description(format!(
+                                                "This invite could not be resolved: {}",
+                                                e
+                                            )),
+                                    ),
+                                )
+                                .await?;
 
-                        return Err(format!("Error resolving invite: {}", e).into());
-                    }
+                            return Err(format!("Error resolving invite: {}", e).into());
+                        }
+                    };
 
                     resp.interaction
                         .edit_response(
@@ -151,113 +172,544 @@ Okay! Now, let's setup the invite for this server! To get started, choose which
                         )
                         .await?;
 
-                    format!("invite_url:{}", invite_url)
+                    InviteSpec::InviteUrl {
+                        url: invite_url.to_string(),
+                    }
                 } else {
                     return Err("Timed out waiting for response for invite URL".into());
                 }
             }
-            "per_user" => {
-                // Ask the user to pick a channel
-                let qm = CreateQuickModal::new("Per-User Invite Selection")
-                    .field(
-                        CreateInputText::new(InputTextStyle::Short, "Enter Channel ID", "channel_id")
-                            .placeholder("Please enter the Channel ID you wish to use!")
-                            .min_length(18)
-                            .max_length(18)
-                            .required(true),
-                    )
-                    .field(
-                        CreateInputText::new(InputTextStyle::Short, "Max Uses", "max_uses")
-                            .placeholder("How many times should a per-user invite be usable for. Use 1 if unsure")
-                            .min_length(1)
-                            .max_length(3)
-                            .required(true),
-                    )
-                    .field(
-                        CreateInputText::new(InputTextStyle::Short, "Max Age", "max_age")
-                            .placeholder("How long should the invite be valid for. Use 0 if unsure")
-                            .min_length(1)
-                            .max_length(3)
-                            .required(true)
-                    );
+            "vanity" => {
+                let code = super::vanity::fetch_vanity_code(&ctx.serenity_context().http, guild_id)
+                    .await?;
 
-                if let Some(resp) = m.quick_modal(ctx.serenity_context(), qm).await? {
-                    // Send a please wait response
-                    resp.interaction
-                        .create_response(
-                            ctx.http(),
-                            CreateInteractionResponse::Message(
-                                CreateInteractionResponseMessage::default().embed(
-                                    CreateEmbed::new()
-                                        .title("Please wait!")
-                                        .description("Please wait..."),
-                                ),
-                            ),
+                m.create_response(
+                    ctx.http(),
+                    CreateInteractionResponse::Message(
+                        CreateInteractionResponseMessage::default().embed(
+                            CreateEmbed::new()
+                                .title("Found vanity URL!")
+                                .description(format!(
+                                    "Infinity List will use https://discord.gg/{} and keep it in sync if it changes",
+                                    code
+                                )),
+                        ),
+                    ),
+                )
+                .await?;
+
+                InviteSpec::Vanity { code }
+            }
+            "per_user" => InviteSpec::PerUser(per_user_invite_view(ctx, m, guild_id).await?),
+            "whitelist" => InviteSpec::Whitelist(per_user_invite_view(ctx, m, guild_id).await?),
+            "application" => {
+                InviteSpec::Application(application_invite_view(ctx, m, guild_id).await?)
+            }
+            "none" => InviteSpec::None {},
+            _ => return Err("Invalid choice".into()),
+        })
+    } else {
+        Err("Timed out waiting for choice".into())
+    }
+}
+
+/// The channel types Discord allows invites to be created in
+const INVITE_CHANNEL_TYPES: [serenity::all::ChannelType; 5] = [
+    serenity::all::ChannelType::Text,
+    serenity::all::ChannelType::Voice,
+    serenity::all::ChannelType::News,
+    serenity::all::ChannelType::Stage,
+    serenity::all::ChannelType::Forum,
+];
+
+/// Asks the user for the settings of a per-user invite
+async fn per_user_invite_view(
+    ctx: &Context<'_>,
+    m: &serenity::all::ComponentInteraction,
+    guild_id: serenity::all::GuildId,
+) -> Result<PerUserInvite, Error> {
+    let (m, channel_id, fallback_channel_ids) =
+        invite_channel_select_view(ctx, m, guild_id).await?;
+
+    let qm = CreateQuickModal::new("Per-User Invite Settings")
+        .field(
+            CreateInputText::new(InputTextStyle::Short, "Max Uses", "max_uses")
+                .placeholder(
+                    "How many times should a per-user invite be usable for. Use 1 if unsure",
+                )
+                .min_length(1)
+                .max_length(3)
+                .required(true),
+        )
+        .field(
+            CreateInputText::new(InputTextStyle::Short, "Max Age", "max_age")
+                .placeholder("How long should the invite be valid for. Use 0 if unsure")
+                .min_length(1)
+                .max_length(6)
+                .required(true),
+        )
+        .field(
+            CreateInputText::new(InputTextStyle::Short, "Temporary Membership", "temporary")
+                .placeholder(
+                    "yes to kick members when they go offline without a role. Use no if unsure",
+                )
+                .max_length(5)
+                .required(false),
+        )
+        .field(
+            CreateInputText::new(InputTextStyle::Short, "Scheduled Event ID", "event_id")
+                .placeholder("The event invites should point to, leave empty for none")
+                .max_length(20)
+                .required(false),
+        );
+
+    if let Some(resp) = m.quick_modal(ctx.serenity_context(), qm).await? {
+        // Send a please wait response
+        resp.interaction
+            .create_response(
+                ctx.http(),
+                CreateInteractionResponse::Message(
+                    CreateInteractionResponseMessage::default().embed(
+                        CreateEmbed::new()
+                            .title("Please wait!")
+                            .description("Please wait..."),
+                    ),
+                ),
+            )
+            .await?;
+
+        let inputs = resp.inputs;
+
+        let per_user = PerUserInvite {
+            channel_id,
+            max_uses: parse_max_uses(&inputs[0])?,
+            max_age: parse_max_age(&inputs[1])?,
+            fallback_channel_ids,
+            temporary: parse_temporary(&inputs[2])?,
+            event_id: parse_event_id(ctx, guild_id, &inputs[3]).await?,
+        };
+
+        per_user.validate()?;
+
+        Ok(per_user)
+    } else {
+        Err("Timed out waiting for response for per-user invite settings".into())
+    }
+}
+
+/// Asks the user to pick the channel (and optionally fallback channels) to create invites in
+///
+/// Returns the interaction that confirmed the selection so that a modal can be shown in response to it
+async fn invite_channel_select_view(
+    ctx: &Context<'_>,
+    m: &serenity::all::ComponentInteraction,
+    guild_id: serenity::all::GuildId,
+) -> Result<
+    (
+        serenity::all::ComponentInteraction,
+        serenity::all::ChannelId,
+        Vec<serenity::all::ChannelId>,
+    ),
+    Error,
+> {
+    m.create_response(
+        ctx.http(),
+        CreateInteractionResponse::Message(
+            CreateInteractionResponseMessage::new()
+                .embed(
+                    CreateEmbed::new()
+                        .title("Invite Channel")
+                        .description("Select the channel Infinity List should create invites in. You can also select fallback channels that will be used, in order, if Infinity List cannot create invites in the first channel. Infinity List needs the **Create Invite** permission in all of them"),
+                )
+                .components(vec![
+                    CreateActionRow::SelectMenu(
+                        CreateSelectMenu::new(
+                            "invite_channel",
+                            CreateSelectMenuKind::Channel {
+                                channel_types: Some(INVITE_CHANNEL_TYPES.to_vec().into()),
+                                default_channels: None,
+                            },
                         )
-                        .await?;
+                        .placeholder("Channel to create invites in"),
+                    ),
+                    CreateActionRow::SelectMenu(
+                        CreateSelectMenu::new(
+                            "fallback_channels",
+                            CreateSelectMenuKind::Channel {
+                                channel_types: Some(INVITE_CHANNEL_TYPES.to_vec().into()),
+                                default_channels: None,
+                            },
+                        )
+                        .placeholder("Fallback channels (optional)")
+                        .min_values(0)
+                        .max_values(MAX_FALLBACK_CHANNELS as u8),
+                    ),
+                    CreateActionRow::Buttons(vec![
+                        CreateButton::new("continue")
+                            .label("Continue")
+                            .style(ButtonStyle::Primary),
+                        CreateButton::new("cancel")
+                            .label("Cancel")
+                            .style(ButtonStyle::Danger),
+                    ]),
+                ]),
+        ),
+    )
+    .await?;
 
-                    let inputs = resp.inputs;
+    let mut msg = m.get_response(ctx.http()).await?;
 
-                    let channel_id: serenity::all::ChannelId = inputs[0].parse()?;
+    let mut channel_id = None;
+    let mut fallback_channel_ids = Vec::new();
 
-                    // Fetch the channel
-                    let channel = channel_id.to_channel(ctx).await?;
+    loop {
+        let Some(interaction) = msg
+            .await_component_interaction(ctx.serenity_context().shard.clone())
+            .author_id(ctx.author().id)
+            .timeout(Duration::from_secs(360))
+            .await
+        else {
+            return Err("Timed out waiting for channel selection".into());
+        };
 
-                    match channel {
-                        serenity::all::Channel::Private(_) => {
-                            return Err("Channel must be a guild channel".into())
-                        }
-                        serenity::all::Channel::Guild(c) => {
-                            if c.guild_id != guild_id {
-                                return Err("Channel must be in this server".into());
-                            }
-                        }
-                        _ => return Err("Channel must be a guild channel".into()),
+        match interaction.data.custom_id.as_str() {
+            "invite_channel" | "fallback_channels" => {
+                if let serenity::all::ComponentInteractionDataKind::ChannelSelect { values } =
+                    &interaction.data.kind
+                {
+                    if interaction.data.custom_id == "invite_channel" {
+                        channel_id = values.first().copied();
+                    } else {
+                        fallback_channel_ids = values.iter().copied().collect();
                     }
+                }
+
+                interaction
+                    .create_response(ctx.http(), CreateInteractionResponse::Acknowledge)
+                    .await?;
+            }
+            "continue" => {
+                let Some(channel_id) = channel_id else {
+                    reply_ephemeral(ctx, &interaction, "Please select a channel first").await?;
+                    continue;
+                };
 
-                    let max_uses: u8 = inputs[1].parse()?;
-                    let max_age: u32 = inputs[2].parse()?;
+                let cache_http = botox::cache::CacheHttpImpl::from_ctx(ctx.serenity_context());
 
-                    format!("per_user:{}:{}:{}", channel_id, max_uses, max_age)
-                } else {
-                    return Err("Timed out waiting for response for channel ID".into());
+                let mut error = None;
+                for c in std::iter::once(channel_id).chain(fallback_channel_ids.iter().copied()) {
+                    if let Err(e) = check_invite_channel(&cache_http, guild_id, c).await {
+                        error = Some(e);
+                        break;
+                    }
+                }
+
+                if let Some(e) = error {
+                    reply_ephemeral(ctx, &interaction, &e.to_string()).await?;
+                    continue;
                 }
+
+                msg.edit(ctx, serenity::all::EditMessage::new().components(vec![]))
+                    .await?;
+
+                fallback_channel_ids.retain(|c| *c != channel_id);
+
+                return Ok((interaction, channel_id, fallback_channel_ids));
             }
-            "none" => "none".to_string(),
-            _ => return Err("Invalid choice".into()),
-        })
+            "cancel" => {
+                interaction
+                    .create_response(ctx.http(), CreateInteractionResponse::Acknowledge)
+                    .await?;
+
+                msg.edit(ctx, serenity::all::EditMessage::new().components(vec![]))
+                    .await?;
+
+                return Err("Setup cancelled".into());
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Sends an ephemeral reply to a component interaction in the setup wizard
+async fn reply_ephemeral(
+    ctx: &Context<'_>,
+    interaction: &serenity::all::ComponentInteraction,
+    message: &str,
+) -> Result<(), Error> {
+    interaction
+        .create_response(
+            ctx.http(),
+            CreateInteractionResponse::Message(
+                CreateInteractionResponseMessage::new()
+                    .content(message)
+                    .ephemeral(true),
+            ),
+        )
+        .await?;
+
+    Ok(())
+}
+
+/// Parses the max uses of an invite, with a user-friendly error
+fn parse_max_uses(input: &str) -> Result<u8, Error> {
+    match input.trim().parse::<u8>() {
+        Ok(max_uses) if max_uses <= MAX_INVITE_USES => Ok(max_uses),
+        _ => Err(format!(
+            "Max uses must be a whole number between 0 and {} (0 = unlimited)",
+            MAX_INVITE_USES
+        )
+        .into()),
+    }
+}
+
+/// Parses the max age of an invite, with a user-friendly error
+fn parse_max_age(input: &str) -> Result<u32, Error> {
+    match input.trim().parse::<u32>() {
+        Ok(max_age) if max_age <= MAX_INVITE_AGE => Ok(max_age),
+        _ => Err(format!(
+            "Max age must be a whole number of seconds between 0 and {} (0 = never expires)",
+            MAX_INVITE_AGE
+        )
+        .into()),
+    }
+}
+
+fn parse_temporary(input: &str) -> Result<bool, Error> {
+    match input.trim().to_lowercase().as_str() {
+        "" | "no" | "n" | "false" => Ok(false),
+        "yes" | "y" | "true" => Ok(true),
+        _ => Err("Temporary membership must be either yes or no".into()),
+    }
+}
+
+/// Parses the ID of a scheduled event, checking that the event exists in the server
+async fn parse_event_id(
+    ctx: &Context<'_>,
+    guild_id: serenity::all::GuildId,
+    input: &str,
+) -> Result<Option<serenity::all::ScheduledEventId>, Error> {
+    let input = input.trim();
+
+    if input.is_empty() {
+        return Ok(None);
+    }
+
+    let event_id: serenity::all::ScheduledEventId = input
+        .parse()
+        .map_err(|_| "The scheduled event ID must be a valid event ID")?;
+
+    let event = guild_id
+        .scheduled_event(ctx.http(), event_id, false)
+        .await
+        .map_err(|_| "Could not find a scheduled event with that ID in this server")?;
+
+    if matches!(
+        event.status,
+        serenity::all::ScheduledEventStatus::Completed
+            | serenity::all::ScheduledEventStatus::Canceled
+    ) {
+        return Err("The scheduled event has already ended".into());
+    }
+
+    Ok(Some(event_id))
+}
+
+/// Asks the user for the settings of a join application invite
+async fn application_invite_view(
+    ctx: &Context<'_>,
+    m: &serenity::all::ComponentInteraction,
+    guild_id: serenity::all::GuildId,
+) -> Result<ApplicationInvite, Error> {
+    let (m, channel_id, fallback_channel_ids) =
+        invite_channel_select_view(ctx, m, guild_id).await?;
+
+    let (m, review_channel_id) = review_channel_select_view(ctx, &m).await?;
+
+    let qm = CreateQuickModal::new("Application Setup")
+        .field(
+            CreateInputText::new(InputTextStyle::Short, "Max Uses", "max_uses")
+                .placeholder("How many times should an invite be usable for. Use 1 if unsure")
+                .min_length(1)
+                .max_length(3)
+                .required(true),
+        )
+        .field(
+            CreateInputText::new(InputTextStyle::Short, "Max Age", "max_age")
+                .placeholder("How long should the invite be valid for. Use 0 if unsure")
+                .min_length(1)
+                .max_length(6)
+                .required(true),
+        )
+        .field(
+            CreateInputText::new(InputTextStyle::Paragraph, "Questions", "questions")
+                .placeholder("The questions applicants must answer, one per line")
+                .min_length(1)
+                .max_length(1500)
+                .required(true),
+        );
+
+    if let Some(resp) = m.quick_modal(ctx.serenity_context(), qm).await? {
+        resp.interaction
+            .create_response(
+                ctx.http(),
+                CreateInteractionResponse::Message(
+                    CreateInteractionResponseMessage::default().embed(
+                        CreateEmbed::new()
+                            .title("Please wait!")
+                            .description("Please wait..."),
+                    ),
+                ),
+            )
+            .await?;
+
+        let inputs = resp.inputs;
+
+        let application = ApplicationInvite {
+            invite: PerUserInvite {
+                channel_id,
+                max_uses: parse_max_uses(&inputs[0])?,
+                max_age: parse_max_age(&inputs[1])?,
+                fallback_channel_ids,
+                temporary: false,
+                event_id: None,
+            },
+            review_channel_id,
+            questions: inputs[2]
+                .lines()
+                .map(|q| q.trim().to_string())
+                .filter(|q| !q.is_empty())
+                .collect(),
+        };
+
+        application.validate()?;
+
+        Ok(application)
     } else {
-        Err("Timed out waiting for choice".into())
+        Err("Timed out waiting for response for application setup".into())
     }
 }
 
-pub async fn resolve_invite(
+/// Asks the user to pick the channel new join applications are posted in for review
+///
+/// Returns the interaction that picked the channel so that a modal can be shown in response to it
+async fn review_channel_select_view(
+    ctx: &Context<'_>,
+    m: &serenity::all::ComponentInteraction,
+) -> Result<
+    (
+        serenity::all::ComponentInteraction,
+        serenity::all::ChannelId,
+    ),
+    Error,
+> {
+    m.create_response(
+        ctx.http(),
+        CreateInteractionResponse::Message(
+            CreateInteractionResponseMessage::new()
+                .embed(
+                    CreateEmbed::new()
+                        .title("Review Channel")
+                        .description("Select the channel new join applications should be posted in for your team to review"),
+                )
+                .components(vec![CreateActionRow::SelectMenu(
+                    CreateSelectMenu::new(
+                        "review_channel",
+                        CreateSelectMenuKind::Channel {
+                            channel_types: Some(
+                                vec![
+                                    serenity::all::ChannelType::Text,
+                                    serenity::all::ChannelType::News,
+                                ]
+                                .into(),
+                            ),
+                            default_channels: None,
+                        },
+                    )
+                    .placeholder("Channel to post applications in"),
+                )]),
+        ),
+    )
+    .await?;
+
+    let mut msg = m.get_response(ctx.http()).await?;
+
+    let Some(interaction) = msg
+        .await_component_interaction(ctx.serenity_context().shard.clone())
+        .author_id(ctx.author().id)
+        .custom_ids(vec!["review_channel".to_string()])
+        .timeout(Duration::from_secs(360))
+        .await
+    else {
+        return Err("Timed out waiting for review channel selection".into());
+    };
+
+    let serenity::all::ComponentInteractionDataKind::ChannelSelect { values } =
+        &interaction.data.kind
+    else {
+        return Err("Invalid review channel selection".into());
+    };
+
+    let Some(review_channel_id) = values.first().copied() else {
+        return Err("Please select a review channel".into());
+    };
+
+    msg.edit(ctx, serenity::all::EditMessage::new().components(vec![]))
+        .await?;
+
+    Ok((interaction, review_channel_id))
+}
+
+/// Checks that a channel is in the given guild, can hold invites and that Infinity List can create invites in it
+pub async fn check_invite_channel(
     cache_http: &botox::cache::CacheHttpImpl,
     guild_id: serenity::all::GuildId,
-    invite: &str,
+    channel_id: serenity::all::ChannelId,
 ) -> Result<(), Error> {
-    // Follow all redirects until reaching end
-    let client = reqwest::Client::builder()
-        .timeout(Duration::from_secs(10))
-        .build()?;
-    let resp = client.get(invite).send().await?;
+    let bot_member = guild_id
+        .member(&cache_http.http, cache_http.cache.current_user().id)
+        .await?;
 
-    info!("Response: {:?}", resp);
+    let permissions = {
+        let Some(guild) = cache_http.cache.guild(guild_id) else {
+            return Err("This server could not be found in cache, please try again later".into());
+        };
 
-    let url = resp.url().to_string();
+        let Some(channel) = guild.channels.get(&channel_id) else {
+            return Err(format!("<#{}> must be a channel in this server", channel_id).into());
+        };
 
-    if !url.starts_with("https://discord.com/invite/") && !url.starts_with("https://discord.gg") {
-        return Err("Invalid invite URL".into());
+        if !INVITE_CHANNEL_TYPES.contains(&channel.kind) {
+            return Err(format!("Invites cannot be created in <#{}>", channel_id).into());
+        }
+
+        guild.user_permissions_in(channel, &bot_member)
+    };
+
+    if !permissions.create_instant_invite() {
+        return Err(format!(
+            "Infinity List needs the **Create Invite** permission in <#{}>",
+            channel_id
+        )
+        .into());
     }
 
-    let code = url
-        .split('/')
-        .last()
-        .ok_or("Invalid invite URL: No code could be parsed")?;
+    Ok(())
+}
+
+/// Checks that an invite URL or code points to the given server and satisfies the configured expiry policy
+///
+/// Returns the canonical ``https://discord.gg/{code}`` URL of the invite
+pub async fn resolve_invite(
+    cache_http: &botox::cache::CacheHttpImpl,
+    guild_id: serenity::all::GuildId,
+    invite: &str,
+) -> Result<String, Error> {
+    let code = super::invitecode::resolve_invite_code(invite).await?;
 
     let invite = cache_http
         .http
-        .get_invite(code, false, true, None)
+        .get_invite(&code, false, true, None)
         .await
         .map_err(|e| format!("Failed to fetch invite: {}", e))?;
 
@@ -270,29 +722,71 @@ pub async fn resolve_invite(
     }
 
     if let Some(e) = invite.expires_at {
-        // Check length to ensure that expiry is at least 30 days
-        let length = e.signed_duration_since(chrono::Utc::now()).num_days();
+        match crate::config::CONFIG.invite_expiry_policy {
+            InviteExpiryPolicy::Permanent => return Err("Invite must be permanent".into()),
+            InviteExpiryPolicy::MinDays { days } => {
+                let length = e.signed_duration_since(chrono::Utc::now()).num_days();
 
-        if length < 30 {
-            return Err("Invite expiry must be after at least 30 days long".into());
+                if length < days {
+                    return Err(format!(
+                        "Invite must be permanent or expire at least {} days from now",
+                        days
+                    )
+                    .into());
+                }
+            }
+            InviteExpiryPolicy::Any => {}
         }
-
-        return Err("Invite must be permanent".into());
     }
 
-    Ok(())
+    Ok(format!("https://discord.gg/{}", code))
 }
 
 /// Represents the error that can occur when creating an invite for a user
 #[derive(Debug, Serialize, Deserialize, ToSchema, TS, Clone, VariantNames)]
 #[ts(export, export_to = ".generated/CreateInviteForUserError.ts")]
 pub enum CreateInviteForUserError {
-    Generic { message: String },
+    Generic {
+        message: String,
+    },
     ServerNotFound {},
+    ServerBotMissing {},
     ServerNeedsLoginForInvite {},
+    InvitesPaused {
+        /// None if invites are paused until resumed by the servers team
+        #[schema(value_type = Option<String>)]
+        until: Option<chrono::DateTime<chrono::Utc>>,
+    },
+    OutsideInviteWindow {
+        /// When invites are next handed out, None if the schedule never opens
+        #[schema(value_type = Option<String>)]
+        next_open: Option<chrono::DateTime<chrono::Utc>>,
+    },
     UserIsBlacklisted {},
+    UserIsBannedFromServer {},
+    UserNotWhitelisted {},
+    AccountTooNew {
+        min_days: u32,
+    },
+    VoteRequired {
+        hours: u32,
+    },
+    TooManyListedServers {
+        max: u32,
+    },
+    ApplicationAnswersRequired {
+        questions: Vec<String>,
+    },
+    ApplicationAlreadyPending {},
+    RateLimited {
+        retry_after: u64,
+    },
+    DailyQuotaExceeded {
+        retry_after: u64,
+    },
     ServerHasNoInvite {},
     ServerHasInvalidInvite {},
+    ServerInviteChannelBroken {},
     ServerTypeNotApprovedOrCertified {},
     ServerStateNotPublic {},
 }
@@ -302,16 +796,95 @@ impl core::fmt::Display for CreateInviteForUserError {
         match self {
             CreateInviteForUserError::Generic { message } => write!(f, "{}", message),
             CreateInviteForUserError::ServerNotFound {} => write!(f, "Server not found"),
+            CreateInviteForUserError::ServerBotMissing {} => {
+                write!(
+                    f,
+                    "Infinity List is no longer in this server, so invites to it cannot be created"
+                )
+            }
             CreateInviteForUserError::ServerNeedsLoginForInvite {} => {
                 write!(f, "In order to view this server, you must login!")
             }
+            CreateInviteForUserError::InvitesPaused { until } => match until {
+                Some(until) => write!(
+                    f,
+                    "Invites to this server are paused until {}",
+                    until.to_rfc2822()
+                ),
+                None => write!(f, "Invites to this server are currently paused"),
+            },
+            CreateInviteForUserError::OutsideInviteWindow { next_open } => match next_open {
+                Some(next_open) => write!(
+                    f,
+                    "This server is not accepting new members right now, please try again at {}",
+                    next_open.to_rfc2822()
+                ),
+                None => write!(f, "This server is not accepting new members right now"),
+            },
             CreateInviteForUserError::UserIsBlacklisted {} => {
                 write!(f, "User is blacklisted from this server")
             }
+            CreateInviteForUserError::UserIsBannedFromServer {} => {
+                write!(f, "User is banned from this server")
+            }
+            CreateInviteForUserError::UserNotWhitelisted {} => {
+                write!(f, "This server only allows whitelisted users to join")
+            }
+            CreateInviteForUserError::AccountTooNew { min_days } => {
+                write!(
+                    f,
+                    "Your Discord account must be at least {} days old to join this server",
+                    min_days
+                )
+            }
+            CreateInviteForUserError::VoteRequired { hours } => {
+                write!(
+                    f,
+                    "You must have voted for this server in the last {} hours to join it",
+                    hours
+                )
+            }
+            CreateInviteForUserError::TooManyListedServers { max } => {
+                write!(
+                    f,
+                    "This server only allows users who are in at most {} other servers on Infinity List",
+                    max
+                )
+            }
+            CreateInviteForUserError::ApplicationAnswersRequired { questions } => {
+                write!(
+                    f,
+                    "This server requires you to answer {} question(s) to apply to join",
+                    questions.len()
+                )
+            }
+            CreateInviteForUserError::ApplicationAlreadyPending {} => {
+                write!(f, "You already have a pending application to this server")
+            }
+            CreateInviteForUserError::RateLimited { retry_after } => {
+                write!(
+                    f,
+                    "You are being ratelimited! Please try again in {} seconds",
+                    retry_after
+                )
+            }
+            CreateInviteForUserError::DailyQuotaExceeded { retry_after } => {
+                write!(
+                    f,
+                    "This server has reached its daily invite limit! Please try again in {} seconds",
+                    retry_after
+                )
+            }
             CreateInviteForUserError::ServerHasNoInvite {} => write!(f, "Server has no invite"),
             CreateInviteForUserError::ServerHasInvalidInvite {} => {
                 write!(f, "Server has an invalid invite")
             }
+            CreateInviteForUserError::ServerInviteChannelBroken {} => {
+                write!(
+                    f,
+                    "This server's invite channel is broken, the server's team has been notified"
+                )
+            }
             CreateInviteForUserError::ServerTypeNotApprovedOrCertified {} => {
                 write!(f, "Server is not approved or certified")
             }
@@ -327,20 +900,23 @@ impl core::fmt::Display for CreateInviteForUserError {
 #[ts(export, export_to = ".generated/CreateInviteForUserResult.ts")]
 pub enum CreateInviteForUserResult {
     Invite { url: String },
+    ApplicationSubmitted { application_id: String },
 }
 
 /// Creates an invite for a user in a guild
 ///
-/// TODO: Improve this with more features if needed (such as whitelist-only servers)
+/// For servers in application mode, this files a join application using ``answers`` instead. If ``skip_checks``
+/// is set, the application is treated as approved and an invite is created directly
 pub async fn create_invite_for_user(
     cache_http: &botox::cache::CacheHttpImpl,
     pool: &sqlx::PgPool,
     guild_id: serenity::all::GuildId,
     user_id: Option<serenity::all::UserId>,
     skip_checks: bool,
+    answers: Option<Vec<String>>,
 ) -> Result<CreateInviteForUserResult, CreateInviteForUserError> {
     let row = sqlx::query!(
-        "SELECT login_required_for_invite, blacklisted_users, invite, type, state FROM servers WHERE server_id = $1",
+        "SELECT login_required_for_invite, check_guild_bans, whitelisted_users, invite_rules, invite_schedule, invite, type, state, bot_missing_since FROM servers WHERE server_id = $1",
         guild_id.to_string()
     )
     .fetch_optional(pool)
@@ -357,15 +933,98 @@ pub async fn create_invite_for_user(
         None => return Err(CreateInviteForUserError::ServerNotFound {}),
     };
 
+    if row.bot_missing_since.is_some() {
+        return Err(CreateInviteForUserError::ServerBotMissing {});
+    }
+
+    // These also apply when checks are skipped (such as approved applications), as the user may have been
+    // blacklisted or banned since. Anonymous users can't be checked, servers that need this should require login
+    if let Some(user_id) = user_id {
+        let blacklisted = super::blacklist::is_blacklisted(pool, guild_id, user_id)
+            .await
+            .map_err(|e| {
+                log::error!("Failed to check blacklist: {}", e);
+                CreateInviteForUserError::Generic {
+                    message: format!("Failed to check blacklist: {}", e),
+                }
+            })?;
+
+        if blacklisted {
+            return Err(CreateInviteForUserError::UserIsBlacklisted {});
+        }
+
+        if row.check_guild_bans {
+            let ban = cache_http
+                .http
+                .get_ban(guild_id, user_id)
+                .await
+                .map_err(|e| {
+                    log::error!("Failed to check guild ban of {}: {}", user_id, e);
+                    CreateInviteForUserError::Generic {
+                        message: format!(
+                            "Failed to check whether you are banned from this server: {}",
+                            e
+                        ),
+                    }
+                })?;
+
+            if ban.is_some() {
+                return Err(CreateInviteForUserError::UserIsBannedFromServer {});
+            }
+        }
+    }
+
     if !skip_checks {
-        if row.login_required_for_invite {
+        let pause = super::pause::get_pause(pool, guild_id).await.map_err(|e| {
+            log::error!("Failed to check invite pause: {}", e);
+            CreateInviteForUserError::Generic {
+                message: format!("Failed to check invite pause: {}", e),
+            }
+        })?;
+
+        if let Some(until) = pause {
+            return Err(CreateInviteForUserError::InvitesPaused { until });
+        }
+
+        if let Some(schedule) = row.invite_schedule {
+            let schedule_err = |e: String| {
+                log::error!("Server {} has an invalid invite schedule: {}", guild_id, e);
+                CreateInviteForUserError::Generic {
+                    message: format!("This server has an invalid invite schedule: {}", e),
+                }
+            };
+
+            let schedule: InviteSchedule =
+                serde_json::from_value(schedule).map_err(|e| schedule_err(e.to_string()))?;
+
+            let now = chrono::Utc::now();
+
+            if !schedule.is_open(now).map_err(schedule_err)? {
+                return Err(CreateInviteForUserError::OutsideInviteWindow {
+                    next_open: schedule.next_open(now).map_err(schedule_err)?,
+                });
+            }
+        }
+
+        if row.login_required_for_invite && user_id.is_none() {
+            return Err(CreateInviteForUserError::ServerNeedsLoginForInvite {});
+        }
+
+        let rules: Vec<InviteRule> = serde_json::from_value(row.invite_rules).map_err(|e| {
+            log::error!("Server {} has invalid invite rules: {}", guild_id, e);
+            CreateInviteForUserError::Generic {
+                message: format!("This server has invalid invite rules: {}", e),
+            }
+        })?;
+
+        if !rules.is_empty() {
+            // Rules can only be evaluated for a known user
             let Some(user_id) = user_id else {
                 return Err(CreateInviteForUserError::ServerNeedsLoginForInvite {});
             };
 
-            if row.blacklisted_users.contains(&user_id.to_string()) {
-                return Err(CreateInviteForUserError::UserIsBlacklisted {});
-            }
+            super::inviterules::check_invite_rules(cache_http, pool, guild_id, user_id, &rules)
+                .await?;
         }
 
         if row.r#type != "approved" && row.r#type != "certified" {
@@ -377,66 +1036,316 @@ pub async fn create_invite_for_user(
         }
     }
 
-    if row.invite == "none" {
-        return Err(CreateInviteForUserError::ServerHasNoInvite {});
-    }
-
-    let invite_splitted = row.invite.split(':').collect::<Vec<_>>();
+    let spec = InviteSpec::from_stored(&row.invite).map_err(|e| {
+        log::error!("Server {} has an invalid invite: {}", guild_id, e);
+        CreateInviteForUserError::ServerHasInvalidInvite {}
+    })?;
 
-    if invite_splitted.len() < 2 {
+    if let Err(e) = spec.validate() {
+        log::error!("Server {} has an invalid invite: {}", guild_id, e);
         return Err(CreateInviteForUserError::ServerHasInvalidInvite {});
     }
 
-    match invite_splitted[0] {
-        "invite_url" => {
-            let invite = invite_splitted[1..].join(":");
-            Ok(CreateInviteForUserResult::Invite { url: invite })
+    // Alongside the result, the invite to log (the invite code, or the URL of a fixed invite URL)
+    let (result, logged_invite) = match &spec {
+        InviteSpec::None {} => return Err(CreateInviteForUserError::ServerHasNoInvite {}),
+        InviteSpec::InviteUrl { url } => (
+            CreateInviteForUserResult::Invite { url: url.clone() },
+            Some(url.clone()),
+        ),
+        InviteSpec::Vanity { code } => {
+            // Never hand out a vanity the server no longer owns, it may already belong to another server
+            let current = cache_http
+                .cache
+                .guild(guild_id)
+                .map(|g| g.vanity_url_code.clone());
+
+            if current.is_some_and(|current| current.as_deref() != Some(code.as_str())) {
+                return Err(CreateInviteForUserError::ServerHasInvalidInvite {});
+            }
+
+            (
+                CreateInviteForUserResult::Invite {
+                    url: format!("https://discord.gg/{}", code),
+                },
+                Some(code.clone()),
+            )
         }
-        "per_user" => {
-            let channel_id = invite_splitted[1]
-                .parse::<serenity::all::ChannelId>()
-                .map_err(|_| CreateInviteForUserError::ServerHasInvalidInvite {})?;
-
-            let max_uses = if invite_splitted.len() >= 3 {
-                invite_splitted[2]
-                    .parse::<u8>()
-                    .map_err(|_| CreateInviteForUserError::ServerHasInvalidInvite {})?
-            } else {
-                1 // default to 1
-            };
-            let max_age = if invite_splitted.len() >= 4 {
-                invite_splitted[3]
-                    .parse::<u32>()
-                    .map_err(|_| CreateInviteForUserError::ServerHasInvalidInvite {})?
+        InviteSpec::PerUser(per_user) => {
+            let code =
+                create_per_user_invite(cache_http, pool, guild_id, per_user, user_id, !skip_checks)
+                    .await?;
+
+            (
+                CreateInviteForUserResult::Invite {
+                    url: per_user.invite_url(&code),
+                },
+                Some(code),
+            )
+        }
+        InviteSpec::Whitelist(per_user) => {
+            if !skip_checks {
+                let Some(user_id) = user_id else {
+                    return Err(CreateInviteForUserError::ServerNeedsLoginForInvite {});
+                };
+
+                if !row.whitelisted_users.contains(&user_id.to_string()) {
+                    return Err(CreateInviteForUserError::UserNotWhitelisted {});
+                }
+            }
+
+            let code =
+                create_per_user_invite(cache_http, pool, guild_id, per_user, user_id, !skip_checks)
+                    .await?;
+
+            (
+                CreateInviteForUserResult::Invite {
+                    url: per_user.invite_url(&code),
+                },
+                Some(code),
+            )
+        }
+        InviteSpec::Application(application) => {
+            if skip_checks {
+                let code = create_per_user_invite(
+                    cache_http,
+                    pool,
+                    guild_id,
+                    &application.invite,
+                    user_id,
+                    false,
+                )
+                .await?;
+
+                (
+                    CreateInviteForUserResult::Invite {
+                        url: application.invite.invite_url(&code),
+                    },
+                    Some(code),
+                )
             } else {
-                300 // default to 5 minutes
-            };
+                let Some(user_id) = user_id else {
+                    return Err(CreateInviteForUserError::ServerNeedsLoginForInvite {});
+                };
 
-            let invite = channel_id
-                .create_invite(
-                    &cache_http.http,
-                    serenity::all::CreateInvite::default()
-                        .max_uses(max_uses)
-                        .max_age(max_age)
-                        .unique(true)
-                        .audit_log_reason(
-                            match user_id {
-                                Some(user_id) => format!("Invite created for user {}", user_id),
-                                None => "Invite created for anonymous user".to_string(),
-                            }
-                            .as_str(),
-                        ),
+                return super::applications::submit_application(
+                    cache_http,
+                    pool,
+                    guild_id,
+                    user_id,
+                    application,
+                    answers,
                 )
-                .await
-                .map_err(|e| {
-                    log::error!("Failed to create invite: {}", e);
-                    CreateInviteForUserError::Generic {
-                        message: format!("Failed to create invite: {}", e),
-                    }
-                })?;
+                .await;
+            }
+        }
+    };
+
+    if let Some(invite) = logged_invite {
+        let per_user = spec.per_user();
 
-            Ok(CreateInviteForUserResult::Invite { url: invite.url() })
+        if let Err(e) = super::invitelog::log_invite(
+            pool,
+            guild_id,
+            user_id,
+            &invite,
+            spec.mode(),
+            per_user.map(|p| p.max_uses),
+            per_user.map(|p| p.max_age),
+        )
+        .await
+        {
+            log::error!("Failed to log invite: {}", e);
         }
-        _ => Err(CreateInviteForUserError::ServerHasInvalidInvite {}),
     }
+
+    Ok(result)
+}
+
+/// Creates a per-user invite on Discord
+///
+/// Returns the invite code. If the user already has an unexpired invite with uses remaining, that invite is returned
+/// instead. Only newly created invites count against the daily invite quota of the server, and only if
+/// ``count_quota`` is set
+async fn create_per_user_invite(
+    cache_http: &botox::cache::CacheHttpImpl,
+    pool: &sqlx::PgPool,
+    guild_id: serenity::all::GuildId,
+    per_user: &PerUserInvite,
+    user_id: Option<serenity::all::UserId>,
+    count_quota: bool,
+) -> Result<String, CreateInviteForUserError> {
+    let mut quota_counted = false;
+
+    for channel_id in per_user.channel_ids() {
+        if let Some(user_id) = user_id {
+            match super::invitestore::find_reusable_invite(
+                pool,
+                guild_id,
+                user_id,
+                channel_id,
+                per_user.temporary,
+            )
+            .await
+            {
+                Ok(Some(code)) => return Ok(code),
+                Ok(None) => {}
+                Err(e) => log::warn!("Failed to fetch reusable invite: {}", e),
+            }
+        }
+
+        if count_quota && !quota_counted {
+            match super::ratelimit::consume_daily_quota(pool, guild_id).await {
+                Ok(Some(retry_after)) => {
+                    return Err(CreateInviteForUserError::DailyQuotaExceeded {
+                        retry_after: retry_after.as_secs() + 1,
+                    })
+                }
+                Ok(None) => quota_counted = true,
+                Err(e) => {
+                    log::error!("Failed to check daily invite quota: {}", e);
+                    return Err(CreateInviteForUserError::Generic {
+                        message: format!("Failed to check daily invite quota: {}", e),
+                    });
+                }
+            }
+        }
+
+        let invite = match channel_id
+            .create_invite(
+                &cache_http.http,
+                serenity::all::CreateInvite::default()
+                    .max_uses(per_user.max_uses)
+                    .max_age(per_user.max_age)
+                    .temporary(per_user.temporary)
+                    .unique(true)
+                    .audit_log_reason(
+                        match user_id {
+                            Some(user_id) => format!("Invite created for user {}", user_id),
+                            None => "Invite created for anonymous user".to_string(),
+                        }
+                        .as_str(),
+                    ),
+            )
+            .await
+        {
+            Ok(invite) => invite,
+            Err(e) if is_channel_broken(&e) => {
+                log::warn!(
+                    "Invite channel {} of {} is broken: {}",
+                    channel_id,
+                    guild_id,
+                    e
+                );
+
+                if channel_id == per_user.channel_id {
+                    notify_channel_broken(cache_http, pool, guild_id, channel_id, &e);
+                }
+
+                continue;
+            }
+            Err(e) => {
+                log::error!("Failed to create invite: {}", e);
+
+                if quota_counted {
+                    release_daily_quota(pool, guild_id).await;
+                }
+
+                return Err(CreateInviteForUserError::Generic {
+                    message: format!("Failed to create invite: {}", e),
+                });
+            }
+        };
+
+        if channel_id == per_user.channel_id {
+            // The primary channel works (again), so notify the team the next time it breaks
+            super::notify::clear_notified(&broken_channel_key(guild_id, channel_id));
+        }
+
+        super::tracker::track_invite(guild_id, &invite.code).await;
+
+        if let Err(e) = super::invitestore::record_invite(
+            pool,
+            guild_id,
+            user_id,
+            channel_id,
+            &invite.code,
+            per_user.max_uses,
+            per_user.max_age,
+            per_user.temporary,
+        )
+        .await
+        {
+            log::error!("Failed to record invite: {}", e);
+        }
+
+        return Ok(invite.code.to_string());
+    }
+
+    if quota_counted {
+        release_daily_quota(pool, guild_id).await;
+    }
+
+    Err(CreateInviteForUserError::ServerInviteChannelBroken {})
+}
+
+/// Gives back a counted invite that could not be created, logging any errors
+async fn release_daily_quota(pool: &sqlx::PgPool, guild_id: serenity::all::GuildId) {
+    if let Err(e) = super::ratelimit::release_daily_quota(pool, guild_id).await {
+        log::error!(
+            "Failed to release daily invite quota of {}: {}",
+            guild_id,
+            e
+        );
+    }
+}
+
+/// Returns whether an error creating an invite means that the channel is unusable (deleted, or the bot lacks access or permissions)
+fn is_channel_broken(e: &serenity::Error) -> bool {
+    match e {
+        serenity::Error::Http(e) => matches!(
+            e.status_code(),
+            Some(serenity::http::StatusCode::FORBIDDEN | serenity::http::StatusCode::NOT_FOUND)
+        ),
+        _ => false,
+    }
+}
+
+fn broken_channel_key(
+    guild_id: serenity::all::GuildId,
+    channel_id: serenity::all::ChannelId,
+) -> String {
+    format!("invite_channel_broken:{}:{}", guild_id, channel_id)
+}
+
+/// Lets the servers team know (once) that the primary invite channel is broken, without delaying the invite
+fn notify_channel_broken(
+    cache_http: &botox::cache::CacheHttpImpl,
+    pool: &sqlx::PgPool,
+    guild_id: serenity::all::GuildId,
+    channel_id: serenity::all::ChannelId,
+    e: &serenity::Error,
+) {
+    let http = cache_http.http.clone();
+    let pool = pool.clone();
+    let embed = CreateEmbed::new()
+        .title("Invite Channel Broken")
+        .description(format!(
+            "Infinity List could not create an invite in <#{}> ({}): {}\n\nFallback channels (if any) are being used in the meantime. Make sure the channel exists and that Infinity List has the Create Invite permission there, or update your invite with `/update`.",
+            channel_id, channel_id, e
+        ));
+
+    tokio::spawn(async move {
+        if let Err(e) = super::notify::notify_team_once(
+            &http,
+            &pool,
+            guild_id,
+            broken_channel_key(guild_id, channel_id),
+            embed,
+        )
+        .await
+        {
+            log::error!("Failed to notify team about broken invite channel: {}", e);
+        }
+    });
 }
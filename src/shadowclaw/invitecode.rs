@@ -0,0 +1,217 @@
+/// Hosts that serve invites directly under ``/{code}``
+const SHORT_HOSTS: [&str; 1] = ["discord.gg"];
+
+/// Hosts that serve invites under ``/invite/{code}``
+const INVITE_HOSTS: [&str; 5] = [
+    "discord.com",
+    "discordapp.com",
+    "ptb.discord.com",
+    "canary.discord.com",
+    "discord.gg",
+];
+
+/// The maximum number of redirects followed when resolving an invite through an allowlisted host
+const MAX_REDIRECTS: usize = 5;
+
+/// Returns whether a string is a syntactically valid invite code
+fn is_valid_code(code: &str) -> bool {
+    (2..=32).contains(&code.len()) && code.chars().all(|c| c.is_ascii_alphanumeric() || c == '-')
+}
+
+/// Splits a URL (with or without a scheme) into its lowercased host and path, dropping any port, query or fragment
+fn split_url(input: &str) -> Option<(String, &str)> {
+    let rest = match input.split_once("://") {
+        Some((scheme, rest)) => {
+            if !scheme.eq_ignore_ascii_case("https") && !scheme.eq_ignore_ascii_case("http") {
+                return None;
+            }
+
+            rest
+        }
+        None => input,
+    };
+
+    let rest = rest.split(['?', '#']).next().unwrap_or_default();
+
+    let (authority, path) = match rest.find('/') {
+        Some(idx) => (&rest[..idx], &rest[idx..]),
+        None => (rest, ""),
+    };
+
+    // Credentials in the authority are never valid for an invite
+    if authority.contains('@') {
+        return None;
+    }
+
+    let host = authority
+        .split(':')
+        .next()
+        .unwrap_or_default()
+        .to_lowercase();
+    let host = host.strip_prefix("www.").unwrap_or(&host).to_string();
+
+    Some((host, path))
+}
+
+/// Parses an invite code out of any form of Discord invite URL, or a bare invite code
+///
+/// Accepts ``discord.gg/{code}``, ``discord.com/invite/{code}``, ``discordapp.com/invite/{code}`` (and the ptb/canary
+/// variants) with or without a scheme or ``www.``, ignoring trailing slashes, query strings and fragments. This never
+/// makes any network requests
+pub fn parse_invite_code(input: &str) -> Option<String> {
+    let input = input.trim();
+
+    if is_valid_code(input) {
+        return Some(input.to_string());
+    }
+
+    let (host, path) = split_url(input)?;
+
+    let segments = path
+        .split('/')
+        .filter(|s| !s.is_empty())
+        .collect::<Vec<_>>();
+
+    let code = match segments.as_slice() {
+        [code] if SHORT_HOSTS.contains(&host.as_str()) => code,
+        ["invite", code] if INVITE_HOSTS.contains(&host.as_str()) => code,
+        _ => return None,
+    };
+
+    if !is_valid_code(code) {
+        return None;
+    }
+
+    Some(code.to_string())
+}
+
+/// Returns whether the input is a URL on one of the given (lowercase) hosts
+fn is_allowlisted(input: &str, hosts: &[String]) -> bool {
+    match split_url(input) {
+        Some((host, _)) => hosts.iter().any(|h| h.eq_ignore_ascii_case(&host)),
+        None => false,
+    }
+}
+
+/// Resolves user input to an invite code
+///
+/// Discord invite URLs and bare codes are parsed offline. Redirects are only followed for URLs on one of the
+/// ``invite_redirect_hosts`` in the config, and every hop must itself be allowlisted or a Discord invite URL
+pub async fn resolve_invite_code(input: &str) -> Result<String, crate::Error> {
+    if let Some(code) = parse_invite_code(input) {
+        return Ok(code);
+    }
+
+    let hosts = &crate::config::CONFIG.invite_redirect_hosts;
+
+    let mut url = input.trim().to_string();
+
+    if !url.starts_with("https://") || !is_allowlisted(&url, hosts) {
+        return Err("Invalid invite URL: Only Discord invite URLs and codes are accepted".into());
+    }
+
+    let client = reqwest::Client::builder()
+        .timeout(std::time::Duration::from_secs(10))
+        .redirect(reqwest::redirect::Policy::none())
+        .build()?;
+
+    for _ in 0..MAX_REDIRECTS {
+        let resp = client.get(&url).send().await?;
+
+        let Some(location) = resp.headers().get(reqwest::header::LOCATION) else {
+            return Err("Invalid invite URL: The link did not redirect to a Discord invite".into());
+        };
+
+        let next = resp.url().join(location.to_str()?)?.to_string();
+
+        if let Some(code) = parse_invite_code(&next) {
+            return Ok(code);
+        }
+
+        if !next.starts_with("https://") || !is_allowlisted(&next, hosts) {
+            return Err("Invalid invite URL: The link redirected to a disallowed host".into());
+        }
+
+        url = next;
+    }
+
+    Err("Invalid invite URL: Too many redirects".into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::parse_invite_code;
+
+    #[test]
+    fn parses_bare_codes() {
+        assert_eq!(parse_invite_code("abcDEF12"), Some("abcDEF12".to_string()));
+        assert_eq!(
+            parse_invite_code("  abc-def  "),
+            Some("abc-def".to_string())
+        );
+    }
+
+    #[test]
+    fn parses_short_urls() {
+        for input in [
+            "discord.gg/abc123",
+            "https://discord.gg/abc123",
+            "http://discord.gg/abc123",
+            "https://www.discord.gg/abc123",
+            "https://discord.gg/abc123/",
+            "https://discord.gg/abc123?event=1234",
+            "https://discord.gg/abc123#foo",
+            "HTTPS://DISCORD.GG/abc123",
+        ] {
+            assert_eq!(
+                parse_invite_code(input),
+                Some("abc123".to_string()),
+                "{}",
+                input
+            );
+        }
+    }
+
+    #[test]
+    fn parses_invite_urls() {
+        for input in [
+            "https://discord.com/invite/abc123",
+            "discord.com/invite/abc123",
+            "https://www.discord.com/invite/abc123",
+            "https://discordapp.com/invite/abc123",
+            "https://ptb.discord.com/invite/abc123",
+            "https://canary.discord.com/invite/abc123",
+            "https://discord.gg/invite/abc123",
+            "https://discord.com/invite/abc123/?foo=bar",
+        ] {
+            assert_eq!(
+                parse_invite_code(input),
+                Some("abc123".to_string()),
+                "{}",
+                input
+            );
+        }
+    }
+
+    #[test]
+    fn rejects_other_urls() {
+        for input in [
+            "",
+            "a",
+            "https://example.com/abc123",
+            "https://example.com/invite/abc123",
+            "https://discord.com/abc123",
+            "https://discord.gg.example.com/abc123",
+            "https://discord.gg@example.com/abc123",
+            "https://evil.com/?r=discord.gg/abc123",
+            "ftp://discord.gg/abc123",
+            "javascript:alert(1)",
+            "https://discord.gg/abc123/extra",
+            "https://discord.gg/abc_123",
+            "https://discord.gg/",
+            "http://127.0.0.1/invite/abc123",
+        ] {
+            assert_eq!(parse_invite_code(input), None, "{}", input);
+        }
+    }
+}
@@ -0,0 +1,153 @@
+use super::invitespec::InviteSpec;
+use serenity::all::{CreateEmbed, GuildId};
+
+/// Removes the scheduled event from per-user invites once the event has ended (or was deleted), so invites no
+/// longer link to it. ``stored`` is the invite as stored in the database. Returns whether the invite spec was changed
+pub async fn clear_ended_event(
+    http: &serenity::all::Http,
+    pool: &sqlx::PgPool,
+    guild_id: GuildId,
+    stored: &str,
+    spec: &mut InviteSpec,
+) -> Result<bool, crate::Error> {
+    let Some(per_user) = spec.per_user_mut() else {
+        return Ok(false);
+    };
+
+    let Some(event_id) = per_user.event_id else {
+        return Ok(false);
+    };
+
+    let ended = match guild_id.scheduled_event(http, event_id, false).await {
+        Ok(event) => matches!(
+            event.status,
+            serenity::all::ScheduledEventStatus::Completed
+                | serenity::all::ScheduledEventStatus::Canceled
+        ),
+        Err(serenity::Error::Http(e))
+            if e.status_code() == Some(serenity::http::StatusCode::NOT_FOUND) =>
+        {
+            true
+        }
+        Err(e) => return Err(e.into()),
+    };
+
+    if !ended {
+        return Ok(false);
+    }
+
+    if let Some(per_user) = spec.per_user_mut() {
+        per_user.event_id = None;
+    }
+
+    // Don't overwrite the invite if it was changed in the meantime
+    sqlx::query!(
+        "UPDATE servers SET invite = $3 WHERE server_id = $1 AND invite = $2",
+        guild_id.to_string(),
+        stored,
+        spec.to_stored()?
+    )
+    .execute(pool)
+    .await?;
+
+    log::info!(
+        "Scheduled event {} of {} ended, removed it from the invite",
+        event_id,
+        guild_id
+    );
+
+    Ok(true)
+}
+
+/// Checks that invites can currently be handed out for a server using the given invite spec
+///
+/// Per-user invites are healthy as long as at least one of their channels works. Vanity invites whose vanity
+/// code changed are updated to the new code
+pub async fn check_invite_health(
+    cache_http: &botox::cache::CacheHttpImpl,
+    pool: &sqlx::PgPool,
+    guild_id: GuildId,
+    spec: &InviteSpec,
+) -> Result<(), crate::Error> {
+    spec.validate()?;
+
+    match spec {
+        InviteSpec::None {} => Ok(()),
+        InviteSpec::InviteUrl { url } => {
+            super::invite::resolve_invite(cache_http, guild_id, url).await?;
+            Ok(())
+        }
+        InviteSpec::Vanity { code } => {
+            let current = super::vanity::fetch_vanity_code(&cache_http.http, guild_id).await?;
+
+            if &current != code {
+                super::vanity::sync_vanity(&cache_http.http, pool, guild_id, Some(&current))
+                    .await?;
+            }
+
+            Ok(())
+        }
+        InviteSpec::PerUser(_) | InviteSpec::Whitelist(_) | InviteSpec::Application(_) => {
+            let Some(per_user) = spec.per_user() else {
+                return Ok(());
+            };
+
+            let mut errors = Vec::new();
+
+            for channel_id in per_user.channel_ids() {
+                match super::invite::check_invite_channel(cache_http, guild_id, channel_id).await {
+                    Ok(()) => return Ok(()),
+                    Err(e) => errors.push(e.to_string()),
+                }
+            }
+
+            Err(errors.join("\n").into())
+        }
+    }
+}
+
+/// Records the result of an invite health check, notifying the team if the invite just broke
+pub async fn record_invite_health(
+    http: &serenity::all::Http,
+    pool: &sqlx::PgPool,
+    guild_id: GuildId,
+    result: Result<(), crate::Error>,
+) -> Result<(), crate::Error> {
+    let (health, message) = match &result {
+        Ok(()) => ("ok", None),
+        Err(e) => ("broken", Some(e.to_string())),
+    };
+
+    // Subqueries in RETURNING see the row as it was before the update
+    let previous = sqlx::query!(
+        "UPDATE servers SET invite_health = $2, invite_health_message = $3, invite_health_checked_at = NOW()
+        WHERE server_id = $1
+        RETURNING (SELECT invite_health FROM servers WHERE server_id = $1) AS previous",
+        guild_id.to_string(),
+        health,
+        message
+    )
+    .fetch_optional(pool)
+    .await?;
+
+    let Some(previous) = previous else {
+        return Ok(());
+    };
+
+    if health == "broken" && previous.previous.as_deref() != Some("broken") {
+        super::notify::notify_team(
+            http,
+            pool,
+            guild_id,
+            CreateEmbed::new()
+                .title("Server Invite Broken")
+                .description(format!(
+                    "Visitors on Infinity List can no longer get an invite to your server:\n\n{}\n\nFix the problem above or update your invite with `/update`.",
+                    message.unwrap_or_default()
+                )),
+        )
+        .await?;
+    }
+
+    Ok(())
+}
@@ -0,0 +1,87 @@
+use serde::{Deserialize, Serialize};
+use serenity::all::{GuildId, UserId};
+use ts_rs::TS;
+use utoipa::ToSchema;
+
+/// The maximum number of invite log entries that can be fetched at once
+pub const MAX_INVITE_LOGS: i64 = 100;
+
+/// An entry in the invite log of a server
+#[derive(Debug, Serialize, Deserialize, ToSchema, TS, Clone)]
+#[ts(export, export_to = ".generated/InviteLogEntry.ts")]
+pub struct InviteLogEntry {
+    pub id: String,
+    /// The user the invite was handed out to, None if anonymous
+    pub user_id: Option<String>,
+    /// The invite code, or the invite URL for InviteUrl invites
+    pub invite: String,
+    /// The invite mode of the server at the time
+    pub mode: String,
+    pub max_uses: Option<i32>,
+    pub max_age: Option<i32>,
+    #[schema(value_type = String)]
+    pub created_at: chrono::DateTime<chrono::Utc>,
+}
+
+/// Records an invite handed out to a user
+pub async fn log_invite(
+    pool: &sqlx::PgPool,
+    guild_id: GuildId,
+    user_id: Option<UserId>,
+    invite: &str,
+    mode: &str,
+    max_uses: Option<u8>,
+    max_age: Option<u32>,
+) -> Result<(), crate::Error> {
+    sqlx::query!(
+        "INSERT INTO server_invite_logs (server_id, user_id, invite, mode, max_uses, max_age) VALUES ($1, $2, $3, $4, $5, $6)",
+        guild_id.to_string(),
+        user_id.map(|u| u.to_string()),
+        invite,
+        mode,
+        max_uses.map(i32::from),
+        max_age.map(i32::try_from).transpose()?
+    )
+    .execute(pool)
+    .await?;
+
+    Ok(())
+}
+
+/// Returns the invite log of a server, newest first
+pub async fn get_invite_logs(
+    pool: &sqlx::PgPool,
+    guild_id: GuildId,
+    limit: i64,
+    offset: i64,
+) -> Result<Vec<InviteLogEntry>, crate::Error> {
+    if !(1..=MAX_INVITE_LOGS).contains(&limit) {
+        return Err(format!("Limit must be between 1 and {}", MAX_INVITE_LOGS).into());
+    }
+
+    if offset < 0 {
+        return Err("Offset cannot be negative".into());
+    }
+
+    let rows = sqlx::query!(
+        "SELECT id, user_id, invite, mode, max_uses, max_age, created_at FROM server_invite_logs WHERE server_id = $1 ORDER BY created_at DESC LIMIT $2 OFFSET $3",
+        guild_id.to_string(),
+        limit,
+        offset
+    )
+    .fetch_all(pool)
+    .await?;
+
+    Ok(rows
+        .into_iter()
+        .map(|r| InviteLogEntry {
+            id: r.id.to_string(),
+            user_id: r.user_id,
+            invite: r.invite,
+            mode: r.mode,
+            max_uses: r.max_uses,
+            max_age: r.max_age,
+            created_at: r.created_at,
+        })
+        .collect())
+}
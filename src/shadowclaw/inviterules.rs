@@ -0,0 +1,189 @@
+use super::invite::CreateInviteForUserError;
+use serde::{Deserialize, Serialize};
+use serenity::all::{GuildId, UserId};
+use ts_rs::TS;
+use utoipa::ToSchema;
+
+/// The maximum number of invite rules a server can have
+pub const MAX_INVITE_RULES: usize = 10;
+
+/// A rule a user must satisfy to get an invite to a server
+#[derive(Debug, Serialize, Deserialize, ToSchema, TS, Clone, PartialEq)]
+#[ts(export, export_to = ".generated/InviteRule.ts")]
+pub enum InviteRule {
+    /// The users Discord account must be at least ``days`` days old
+    MinAccountAge { days: u32 },
+    /// The user must have voted for the server in the last ``hours`` hours
+    RecentVote { hours: u32 },
+    /// The user must not be in more than ``count`` other servers listed on Infinity List
+    ///
+    /// Membership is taken from the member cache, so this is only enforced for servers the bot can see members of
+    MaxListedServers { count: u32 },
+}
+
+impl InviteRule {
+    /// Returns the name of the rule, used in user-facing messages
+    pub fn name(&self) -> &'static str {
+        match self {
+            InviteRule::MinAccountAge { .. } => "MinAccountAge",
+            InviteRule::RecentVote { .. } => "RecentVote",
+            InviteRule::MaxListedServers { .. } => "MaxListedServers",
+        }
+    }
+}
+
+/// Validates a set of invite rules, returning an error message describing the problem if any
+///
+/// ``intents`` are the gateway intents of the bot, as some rules depend on the member cache
+pub fn validate_invite_rules(
+    rules: &[InviteRule],
+    intents: serenity::all::GatewayIntents,
+) -> Result<(), String> {
+    if rules.len() > MAX_INVITE_RULES {
+        return Err(format!(
+            "A server can have at most {} invite rules",
+            MAX_INVITE_RULES
+        ));
+    }
+
+    for (i, rule) in rules.iter().enumerate() {
+        if rules[..i].iter().any(|r| r.name() == rule.name()) {
+            return Err(format!("The {} rule can only be used once", rule.name()));
+        }
+
+        match rule {
+            InviteRule::MinAccountAge { days } if *days == 0 || *days > 3650 => {
+                return Err("Minimum account age must be between 1 and 3650 days".to_string());
+            }
+            InviteRule::RecentVote { hours } if *hours == 0 || *hours > 24 * 30 => {
+                return Err("The vote window must be between 1 and 720 hours".to_string());
+            }
+            InviteRule::MaxListedServers { .. }
+                if !intents.contains(serenity::all::GatewayIntents::GUILD_MEMBERS) =>
+            {
+                return Err("The MaxListedServers rule is unavailable as Infinity List cannot see the members of servers".to_string());
+            }
+            _ => {}
+        }
+    }
+
+    Ok(())
+}
+
+/// Returns the invite rules of a server
+pub async fn get_invite_rules(
+    pool: &sqlx::PgPool,
+    guild_id: GuildId,
+) -> Result<Vec<InviteRule>, crate::Error> {
+    let row = sqlx::query!(
+        "SELECT invite_rules FROM servers WHERE server_id = $1",
+        guild_id.to_string()
+    )
+    .fetch_optional(pool)
+    .await?;
+
+    let Some(row) = row else {
+        return Err("This server is not on Infinity List!".into());
+    };
+
+    Ok(serde_json::from_value(row.invite_rules)?)
+}
+
+/// Replaces the invite rules of a server, returning the new rules
+pub async fn set_invite_rules(
+    pool: &sqlx::PgPool,
+    guild_id: GuildId,
+    rules: Vec<InviteRule>,
+    intents: serenity::all::GatewayIntents,
+) -> Result<Vec<InviteRule>, crate::Error> {
+    validate_invite_rules(&rules, intents)?;
+
+    let res = sqlx::query!(
+        "UPDATE servers SET invite_rules = $2 WHERE server_id = $1",
+        guild_id.to_string(),
+        serde_json::to_value(&rules)?
+    )
+    .execute(pool)
+    .await?;
+
+    if res.rows_affected() == 0 {
+        return Err("This server is not on Infinity List!".into());
+    }
+
+    Ok(rules)
+}
+
+/// Checks that a user satisfies all of the given invite rules
+pub async fn check_invite_rules(
+    cache_http: &botox::cache::CacheHttpImpl,
+    pool: &sqlx::PgPool,
+    guild_id: GuildId,
+    user_id: UserId,
+    rules: &[InviteRule],
+) -> Result<(), CreateInviteForUserError> {
+    let db_err = |e: sqlx::Error| {
+        log::error!("Failed to check invite rules: {}", e);
+        CreateInviteForUserError::Generic {
+            message: format!("Failed to check invite rules: {}", e),
+        }
+    };
+
+    for rule in rules {
+        match rule {
+            InviteRule::MinAccountAge { days } => {
+                let age = chrono::Utc::now().timestamp() - user_id.created_at().unix_timestamp();
+
+                if age < i64::from(*days) * 86400 {
+                    return Err(CreateInviteForUserError::AccountTooNew { min_days: *days });
+                }
+            }
+            InviteRule::RecentVote { hours } => {
+                let rec = sqlx::query!(
+                    "SELECT COUNT(*) FROM entity_votes WHERE target_id = $1 AND target_type = 'server' AND author = $2 AND upvote = true AND void = false AND created_at > NOW() - make_interval(hours => $3)",
+                    guild_id.to_string(),
+                    user_id.to_string(),
+                    i32::try_from(*hours).unwrap_or(i32::MAX)
+                )
+                .fetch_one(pool)
+                .await
+                .map_err(db_err)?;
+
+                if rec.count.unwrap_or(0) == 0 {
+                    return Err(CreateInviteForUserError::VoteRequired { hours: *hours });
+                }
+            }
+            InviteRule::MaxListedServers { count } => {
+                // Only cached guilds can be checked, so only ask for those instead of every listed server
+                let joined_guilds = cache_http
+                    .cache
+                    .guilds()
+                    .into_iter()
+                    .filter(|g| *g != guild_id)
+                    .filter(|g| {
+                        cache_http
+                            .cache
+                            .guild(*g)
+                            .is_some_and(|g| g.members.contains_key(&user_id))
+                    })
+                    .map(|g| g.to_string())
+                    .collect::<Vec<_>>();
+
+                let listed = sqlx::query!(
+                    "SELECT COUNT(*) FROM servers WHERE server_id = ANY($1)",
+                    &joined_guilds
+                )
+                .fetch_one(pool)
+                .await
+                .map_err(db_err)?;
+
+                let joined = listed.count.unwrap_or(0);
+
+                if joined > i64::from(*count) {
+                    return Err(CreateInviteForUserError::TooManyListedServers { max: *count });
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
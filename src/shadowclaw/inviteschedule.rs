@@ -0,0 +1,359 @@
+use chrono::{DateTime, Datelike, Duration, TimeZone, Timelike, Utc};
+use serde::{Deserialize, Serialize};
+use serenity::all::GuildId;
+use ts_rs::TS;
+use utoipa::ToSchema;
+
+/// The maximum number of windows an invite schedule can have
+pub const MAX_INVITE_WINDOWS: usize = 28;
+
+const DAYS: [&str; 7] = ["mon", "tue", "wed", "thu", "fri", "sat", "sun"];
+const DAY_NAMES: [&str; 7] = [
+    "monday",
+    "tuesday",
+    "wednesday",
+    "thursday",
+    "friday",
+    "saturday",
+    "sunday",
+];
+
+/// A weekly time range in which invites are handed out
+#[derive(Debug, Serialize, Deserialize, ToSchema, TS, Clone, PartialEq)]
+#[ts(export, export_to = ".generated/InviteWindow.ts")]
+pub struct InviteWindow {
+    /// The day of the week, 0 = Monday and 6 = Sunday
+    pub day: u8,
+    /// Minutes since midnight at which the window opens
+    pub start: u16,
+    /// Minutes since midnight at which the window closes (at most 1440)
+    pub end: u16,
+}
+
+/// The weekly schedule in which a server accepts new members through Infinity List
+#[derive(Debug, Serialize, Deserialize, ToSchema, TS, Clone, PartialEq)]
+#[ts(export, export_to = ".generated/InviteSchedule.ts")]
+pub struct InviteSchedule {
+    /// An IANA timezone name, such as ``Europe/Berlin``
+    pub timezone: String,
+    pub windows: Vec<InviteWindow>,
+}
+
+fn parse_time(s: &str) -> Option<u16> {
+    let (h, m) = s.trim().split_once(':')?;
+    let (h, m) = (h.parse::<u16>().ok()?, m.parse::<u16>().ok()?);
+
+    if m >= 60 || h > 24 || (h == 24 && m != 0) {
+        return None;
+    }
+
+    Some(h * 60 + m)
+}
+
+fn format_time(minutes: u16) -> String {
+    format!("{:02}:{:02}", minutes / 60, minutes % 60)
+}
+
+impl InviteSchedule {
+    fn tz(&self) -> Result<chrono_tz::Tz, String> {
+        self.timezone
+            .parse::<chrono_tz::Tz>()
+            .map_err(|_| format!("Unknown timezone: {}", self.timezone))
+    }
+
+    /// Validates the schedule, returning an error message describing the problem if any
+    pub fn validate(&self) -> Result<(), String> {
+        self.tz()?;
+
+        if self.windows.is_empty() || self.windows.len() > MAX_INVITE_WINDOWS {
+            return Err(format!(
+                "A schedule must have between 1 and {} windows",
+                MAX_INVITE_WINDOWS
+            ));
+        }
+
+        for window in &self.windows {
+            if window.day > 6 {
+                return Err("Window days must be between 0 (Monday) and 6 (Sunday)".to_string());
+            }
+
+            if window.start >= window.end || window.end > 1440 {
+                return Err(format!(
+                    "Invalid window on {}: it must start before it ends",
+                    DAYS[window.day as usize]
+                ));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Parses windows in the form ``mon 09:00-17:00`` (or ``monday 09:00-17:00``), one per line
+    pub fn parse_windows(s: &str) -> Result<Vec<InviteWindow>, String> {
+        let mut windows = Vec::new();
+
+        for line in s.lines().map(|l| l.trim()).filter(|l| !l.is_empty()) {
+            let err = || {
+                format!(
+                    "Invalid window `{}`, windows must look like `mon 09:00-17:00`",
+                    line
+                )
+            };
+
+            let (day, range) = line.split_once(' ').ok_or_else(err)?;
+            let (start, end) = range.split_once('-').ok_or_else(err)?;
+
+            let day = day.trim().to_lowercase();
+            let day = DAYS
+                .iter()
+                .zip(DAY_NAMES.iter())
+                .position(|(short, long)| day == *short || day == *long)
+                .ok_or_else(err)?;
+
+            windows.push(InviteWindow {
+                day: day as u8,
+                start: parse_time(start).ok_or_else(err)?,
+                end: parse_time(end).ok_or_else(err)?,
+            });
+        }
+
+        Ok(windows)
+    }
+
+    /// Formats the windows in the format accepted by ``parse_windows``
+    pub fn format_windows(&self) -> String {
+        self.windows
+            .iter()
+            .map(|w| {
+                format!(
+                    "{} {}-{}",
+                    DAYS[w.day as usize % 7],
+                    format_time(w.start),
+                    format_time(w.end)
+                )
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    /// Returns whether invites are handed out at the given time
+    pub fn is_open(&self, now: DateTime<Utc>) -> Result<bool, String> {
+        let local = now.with_timezone(&self.tz()?);
+        let day = local.weekday().num_days_from_monday() as u8;
+        let minute = (local.hour() * 60 + local.minute()) as u16;
+
+        Ok(self
+            .windows
+            .iter()
+            .any(|w| w.day == day && w.start <= minute && minute < w.end))
+    }
+
+    /// Returns the next time a window opens after the given time, None if there is none
+    pub fn next_open(&self, now: DateTime<Utc>) -> Result<Option<DateTime<Utc>>, String> {
+        let tz = self.tz()?;
+        let local = now.with_timezone(&tz);
+
+        let mut next = None;
+
+        for offset in 0..=7 {
+            let date = local.date_naive() + Duration::days(offset);
+            let day = date.weekday().num_days_from_monday() as u8;
+
+            for window in self.windows.iter().filter(|w| w.day == day) {
+                let Some(naive) =
+                    date.and_hms_opt((window.start / 60).into(), (window.start % 60).into(), 0)
+                else {
+                    continue;
+                };
+
+                // Skip over DST gaps
+                let opens = match tz.from_local_datetime(&naive).earliest() {
+                    Some(opens) => opens,
+                    None => match tz
+                        .from_local_datetime(&(naive + Duration::hours(1)))
+                        .earliest()
+                    {
+                        Some(opens) => opens,
+                        None => continue,
+                    },
+                }
+                .with_timezone(&Utc);
+
+                if opens > now && next.is_none_or(|n| opens < n) {
+                    next = Some(opens);
+                }
+            }
+
+            if next.is_some() {
+                break;
+            }
+        }
+
+        Ok(next)
+    }
+}
+
+/// Returns the invite schedule of a server, None if invites are handed out at all times
+pub async fn get_invite_schedule(
+    pool: &sqlx::PgPool,
+    guild_id: GuildId,
+) -> Result<Option<InviteSchedule>, crate::Error> {
+    let row = sqlx::query!(
+        "SELECT invite_schedule FROM servers WHERE server_id = $1",
+        guild_id.to_string()
+    )
+    .fetch_optional(pool)
+    .await?;
+
+    let Some(row) = row else {
+        return Err("This server is not on Infinity List!".into());
+    };
+
+    match row.invite_schedule {
+        Some(schedule) => Ok(Some(serde_json::from_value(schedule)?)),
+        None => Ok(None),
+    }
+}
+
+/// Sets (or clears, if None) the invite schedule of a server, returning the new schedule
+pub async fn set_invite_schedule(
+    pool: &sqlx::PgPool,
+    guild_id: GuildId,
+    schedule: Option<InviteSchedule>,
+) -> Result<Option<InviteSchedule>, crate::Error> {
+    if let Some(schedule) = &schedule {
+        schedule.validate()?;
+    }
+
+    let res = sqlx::query!(
+        "UPDATE servers SET invite_schedule = $2 WHERE server_id = $1",
+        guild_id.to_string(),
+        schedule.as_ref().map(serde_json::to_value).transpose()?
+    )
+    .execute(pool)
+    .await?;
+
+    if res.rows_affected() == 0 {
+        return Err("This server is not on Infinity List!".into());
+    }
+
+    Ok(schedule)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{InviteSchedule, InviteWindow};
+    use chrono::{TimeZone, Utc};
+
+    fn schedule(timezone: &str, windows: &str) -> InviteSchedule {
+        InviteSchedule {
+            timezone: timezone.to_string(),
+            windows: InviteSchedule::parse_windows(windows).unwrap(),
+        }
+    }
+
+    #[test]
+    fn parses_windows() {
+        assert_eq!(
+            InviteSchedule::parse_windows("mon 09:00-17:00\n\n  Sunday 22:30-24:00  ").unwrap(),
+            vec![
+                InviteWindow {
+                    day: 0,
+                    start: 540,
+                    end: 1020
+                },
+                InviteWindow {
+                    day: 6,
+                    start: 1350,
+                    end: 1440
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn rejects_invalid_windows() {
+        for input in [
+            "monkey 09:00-17:00",
+            "mo 09:00-17:00",
+            "mond 09:00-17:00",
+            "mon 9-17",
+            "mon 09:00",
+            "mon 09:60-17:00",
+            "mon 09:00-24:01",
+        ] {
+            assert!(
+                InviteSchedule::parse_windows(input).is_err(),
+                "{} should be rejected",
+                input
+            );
+        }
+    }
+
+    #[test]
+    fn formats_windows() {
+        let schedule = schedule("UTC", "tuesday 08:05-12:00\nsun 22:00-24:00");
+        assert_eq!(
+            schedule.format_windows(),
+            "tue 08:05-12:00\nsun 22:00-24:00"
+        );
+    }
+
+    #[test]
+    fn window_boundaries() {
+        // 2024-01-01 is a Monday
+        let schedule = schedule("UTC", "mon 09:00-17:00\nsun 22:00-24:00");
+
+        let at = |d, h, m| Utc.with_ymd_and_hms(2024, 1, d, h, m, 0).unwrap();
+
+        assert!(!schedule.is_open(at(1, 8, 59)).unwrap());
+        assert!(schedule.is_open(at(1, 9, 0)).unwrap());
+        assert!(schedule.is_open(at(1, 16, 59)).unwrap());
+        assert!(!schedule.is_open(at(1, 17, 0)).unwrap());
+        assert!(schedule.is_open(at(7, 23, 59)).unwrap());
+        assert!(!schedule.is_open(at(8, 0, 0)).unwrap());
+    }
+
+    #[test]
+    fn next_open_wraps_around_the_week() {
+        let schedule = schedule("UTC", "mon 09:00-17:00");
+
+        // Sunday, the window opens the next day
+        assert_eq!(
+            schedule
+                .next_open(Utc.with_ymd_and_hms(2024, 1, 7, 12, 0, 0).unwrap())
+                .unwrap(),
+            Some(Utc.with_ymd_and_hms(2024, 1, 8, 9, 0, 0).unwrap())
+        );
+
+        // Monday after the window closed, the window opens a week later
+        assert_eq!(
+            schedule
+                .next_open(Utc.with_ymd_and_hms(2024, 1, 1, 18, 0, 0).unwrap())
+                .unwrap(),
+            Some(Utc.with_ymd_and_hms(2024, 1, 8, 9, 0, 0).unwrap())
+        );
+    }
+
+    #[test]
+    fn next_open_skips_dst_gaps() {
+        // Clocks in New York jump from 02:00 to 03:00 on 2024-03-10, so 02:30 doesn't exist that day
+        let schedule = schedule("America/New_York", "sun 02:30-05:00");
+
+        assert_eq!(
+            schedule
+                .next_open(Utc.with_ymd_and_hms(2024, 3, 9, 12, 0, 0).unwrap())
+                .unwrap(),
+            // 03:30 EDT
+            Some(Utc.with_ymd_and_hms(2024, 3, 10, 7, 30, 0).unwrap())
+        );
+
+        // A week later the window opens at 02:30 EDT again
+        assert_eq!(
+            schedule
+                .next_open(Utc.with_ymd_and_hms(2024, 3, 11, 12, 0, 0).unwrap())
+                .unwrap(),
+            Some(Utc.with_ymd_and_hms(2024, 3, 17, 6, 30, 0).unwrap())
+        );
+    }
+}
@@ -0,0 +1,377 @@
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+use utoipa::ToSchema;
+
+/// The current version of the stored invite spec format
+///
+/// Bump this (and add a migration in ``InviteSpec::from_stored``) whenever the layout of ``InviteSpec`` changes in a non-backwards compatible way
+pub const INVITE_SPEC_VERSION: u32 = 1;
+
+/// The maximum number of uses Discord allows on an invite (0 = unlimited)
+pub const MAX_INVITE_USES: u8 = 100;
+
+/// The maximum age Discord allows on an invite in seconds (0 = never expires)
+pub const MAX_INVITE_AGE: u32 = 604800;
+
+/// The maximum number of fallback channels a per-user invite can have
+pub const MAX_FALLBACK_CHANNELS: usize = 4;
+
+/// Settings for invites that Infinity List creates on a per-user basis
+#[derive(Debug, Serialize, Deserialize, ToSchema, TS, Clone, PartialEq)]
+#[ts(export, export_to = ".generated/PerUserInvite.ts")]
+pub struct PerUserInvite {
+    /// The channel to create the invite in
+    #[ts(type = "string")]
+    pub channel_id: serenity::all::ChannelId,
+    /// The maximum number of uses of the invite (0 = unlimited)
+    pub max_uses: u8,
+    /// The maximum age of the invite in seconds (0 = never expires)
+    pub max_age: u32,
+    /// Channels to try, in order, if the invite cannot be created in ``channel_id``
+    #[serde(default)]
+    #[ts(type = "string[]")]
+    pub fallback_channel_ids: Vec<serenity::all::ChannelId>,
+    /// Whether the invite only grants temporary membership (members are kicked when they go offline without a role)
+    #[serde(default)]
+    pub temporary: bool,
+    /// The scheduled event the invite points to, if any
+    #[serde(default)]
+    #[ts(type = "string | null")]
+    #[schema(value_type = Option<String>)]
+    pub event_id: Option<serenity::all::ScheduledEventId>,
+}
+
+/// The maximum number of questions a join application can have
+pub const MAX_APPLICATION_QUESTIONS: usize = 5;
+
+/// The maximum length of a join application question (Discord caps embed field names at 256 characters)
+pub const MAX_APPLICATION_QUESTION_LENGTH: usize = 256;
+
+/// Settings for servers that review join applications before sending out an invite
+#[derive(Debug, Serialize, Deserialize, ToSchema, TS, Clone, PartialEq)]
+#[ts(export, export_to = ".generated/ApplicationInvite.ts")]
+pub struct ApplicationInvite {
+    /// The per-user invite to send to approved applicants
+    pub invite: PerUserInvite,
+    /// The channel in which new applications are posted for review
+    #[ts(type = "string")]
+    pub review_channel_id: serenity::all::ChannelId,
+    /// The questions applicants must answer
+    pub questions: Vec<String>,
+}
+
+/// Represents how invites to a server are created
+#[derive(Debug, Serialize, Deserialize, ToSchema, TS, Clone, PartialEq)]
+#[ts(export, export_to = ".generated/InviteSpec.ts")]
+pub enum InviteSpec {
+    /// Invites are disabled for this server
+    None {},
+    /// A fixed invite URL
+    InviteUrl { url: String },
+    /// The servers vanity invite, kept in sync with Discord
+    Vanity { code: String },
+    /// Infinity List creates an invite for each user
+    PerUser(PerUserInvite),
+    /// Infinity List creates an invite for each user on the servers whitelist
+    Whitelist(PerUserInvite),
+    /// Users file a join application which, once approved, sends them a per-user invite
+    Application(ApplicationInvite),
+}
+
+/// On-disk representation of an ``InviteSpec``, stored as JSON in ``servers.invite``
+#[derive(Serialize, Deserialize)]
+struct StoredInviteSpec {
+    version: u32,
+    spec: InviteSpec,
+}
+
+impl InviteSpec {
+    /// Returns the name of the invite mode, used in logs and user-facing messages
+    pub fn mode(&self) -> &'static str {
+        match self {
+            InviteSpec::None {} => "none",
+            InviteSpec::InviteUrl { .. } => "invite_url",
+            InviteSpec::Vanity { .. } => "vanity",
+            InviteSpec::PerUser(_) => "per_user",
+            InviteSpec::Whitelist(_) => "whitelist",
+            InviteSpec::Application(_) => "application",
+        }
+    }
+
+    /// Returns the settings used to create per-user invites, if this invite mode creates them
+    pub fn per_user(&self) -> Option<&PerUserInvite> {
+        match self {
+            InviteSpec::None {} | InviteSpec::InviteUrl { .. } | InviteSpec::Vanity { .. } => None,
+            InviteSpec::PerUser(p) | InviteSpec::Whitelist(p) => Some(p),
+            InviteSpec::Application(a) => Some(&a.invite),
+        }
+    }
+
+    /// Mutable version of ``per_user``
+    pub fn per_user_mut(&mut self) -> Option<&mut PerUserInvite> {
+        match self {
+            InviteSpec::None {} | InviteSpec::InviteUrl { .. } | InviteSpec::Vanity { .. } => None,
+            InviteSpec::PerUser(p) | InviteSpec::Whitelist(p) => Some(p),
+            InviteSpec::Application(a) => Some(&mut a.invite),
+        }
+    }
+
+    /// Validates the invite spec, returning an error message describing the problem if any
+    pub fn validate(&self) -> Result<(), String> {
+        match self {
+            InviteSpec::None {} => Ok(()),
+            InviteSpec::InviteUrl { url } => {
+                if url.trim().is_empty() {
+                    return Err("Invite URL cannot be empty".to_string());
+                }
+
+                Ok(())
+            }
+            InviteSpec::Vanity { code } => {
+                if code.trim().is_empty() {
+                    return Err("Vanity code cannot be empty".to_string());
+                }
+
+                Ok(())
+            }
+            InviteSpec::PerUser(p) | InviteSpec::Whitelist(p) => p.validate(),
+            InviteSpec::Application(a) => a.validate(),
+        }
+    }
+
+    /// Serializes the invite spec into the format stored in the database
+    pub fn to_stored(&self) -> Result<String, crate::Error> {
+        Ok(serde_json::to_string(&StoredInviteSpec {
+            version: INVITE_SPEC_VERSION,
+            spec: self.clone(),
+        })?)
+    }
+
+    /// Parses an invite spec from the database
+    ///
+    /// Both the versioned JSON format and the legacy colon-delimited format (``none``, ``invite_url:{invite}``
+    /// and ``per_user:{channel_id}:{max_uses}:{max_age}``) are accepted
+    pub fn from_stored(s: &str) -> Result<Self, crate::Error> {
+        if !s.starts_with('{') {
+            return Self::from_legacy(s);
+        }
+
+        let stored: StoredInviteSpec = serde_json::from_str(s)?;
+
+        if stored.version > INVITE_SPEC_VERSION {
+            return Err(format!("Unsupported invite spec version: {}", stored.version).into());
+        }
+
+        Ok(stored.spec)
+    }
+
+    /// Parses the legacy colon-delimited invite format
+    pub fn from_legacy(s: &str) -> Result<Self, crate::Error> {
+        if s == "none" {
+            return Ok(InviteSpec::None {});
+        }
+
+        let splitted = s.split(':').collect::<Vec<_>>();
+
+        if splitted.len() < 2 {
+            return Err(format!("Invalid legacy invite: {}", s).into());
+        }
+
+        match splitted[0] {
+            "invite_url" => Ok(InviteSpec::InviteUrl {
+                url: splitted[1..].join(":"),
+            }),
+            "per_user" => Ok(InviteSpec::PerUser(PerUserInvite {
+                channel_id: splitted[1].parse()?,
+                max_uses: match splitted.get(2) {
+                    Some(v) => v.parse()?,
+                    None => 1, // default to 1
+                },
+                max_age: match splitted.get(3) {
+                    Some(v) => v.parse()?,
+                    None => 300, // default to 5 minutes
+                },
+                fallback_channel_ids: Vec::new(),
+                temporary: false,
+                event_id: None,
+            })),
+            _ => Err(format!("Unknown legacy invite type: {}", splitted[0]).into()),
+        }
+    }
+}
+
+impl PerUserInvite {
+    /// Returns the channels to try creating the invite in, primary channel first
+    pub fn channel_ids(&self) -> impl Iterator<Item = serenity::all::ChannelId> + '_ {
+        std::iter::once(self.channel_id).chain(self.fallback_channel_ids.iter().copied())
+    }
+
+    /// Returns the URL of an invite created with these settings
+    pub fn invite_url(&self, code: &str) -> String {
+        match self.event_id {
+            Some(event_id) => format!("https://discord.gg/{}?event={}", code, event_id),
+            None => format!("https://discord.gg/{}", code),
+        }
+    }
+
+    /// Validates the per-user invite settings against Discord's limits
+    pub fn validate(&self) -> Result<(), String> {
+        if self.max_uses > MAX_INVITE_USES {
+            return Err(format!(
+                "Max uses must be between 0 and {} (0 = unlimited)",
+                MAX_INVITE_USES
+            ));
+        }
+
+        if self.max_age > MAX_INVITE_AGE {
+            return Err(format!(
+                "Max age must be between 0 and {} seconds (0 = never expires)",
+                MAX_INVITE_AGE
+            ));
+        }
+
+        if self.fallback_channel_ids.len() > MAX_FALLBACK_CHANNELS {
+            return Err(format!(
+                "There can be at most {} fallback channels",
+                MAX_FALLBACK_CHANNELS
+            ));
+        }
+
+        let mut seen = std::collections::HashSet::new();
+        if !self.channel_ids().all(|c| seen.insert(c)) {
+            return Err(
+                "Fallback channels must not repeat the invite channel or each other".to_string(),
+            );
+        }
+
+        Ok(())
+    }
+}
+
+impl ApplicationInvite {
+    /// Validates the application settings
+    pub fn validate(&self) -> Result<(), String> {
+        self.invite.validate()?;
+
+        if self.questions.is_empty() || self.questions.len() > MAX_APPLICATION_QUESTIONS {
+            return Err(format!(
+                "Applications must have between 1 and {} questions",
+                MAX_APPLICATION_QUESTIONS
+            ));
+        }
+
+        for question in &self.questions {
+            if question.trim().is_empty() || question.len() > MAX_APPLICATION_QUESTION_LENGTH {
+                return Err(format!(
+                    "Application questions must be between 1 and {} characters long",
+                    MAX_APPLICATION_QUESTION_LENGTH
+                ));
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ApplicationInvite, InviteSpec, PerUserInvite};
+    use serenity::all::ChannelId;
+
+    fn per_user(channel_id: u64, max_uses: u8, max_age: u32) -> PerUserInvite {
+        PerUserInvite {
+            channel_id: ChannelId::new(channel_id),
+            max_uses,
+            max_age,
+            fallback_channel_ids: Vec::new(),
+            temporary: false,
+            event_id: None,
+        }
+    }
+
+    /// Parses a legacy invite and checks that it survives being stored and read back
+    fn round_trip(legacy: &str) -> InviteSpec {
+        let spec = InviteSpec::from_legacy(legacy).unwrap();
+
+        assert_eq!(InviteSpec::from_stored(legacy).unwrap(), spec);
+        assert_eq!(
+            InviteSpec::from_stored(&spec.to_stored().unwrap()).unwrap(),
+            spec
+        );
+
+        spec
+    }
+
+    #[test]
+    fn round_trips_legacy_invites() {
+        assert_eq!(round_trip("none"), InviteSpec::None {});
+        assert_eq!(
+            round_trip("invite_url:https://discord.gg/abc"),
+            InviteSpec::InviteUrl {
+                url: "https://discord.gg/abc".to_string()
+            }
+        );
+        assert_eq!(
+            round_trip("per_user:1234567890:5:3600"),
+            InviteSpec::PerUser(per_user(1234567890, 5, 3600))
+        );
+    }
+
+    #[test]
+    fn legacy_per_user_defaults() {
+        assert_eq!(
+            round_trip("per_user:1234567890"),
+            InviteSpec::PerUser(per_user(1234567890, 1, 300))
+        );
+        assert_eq!(
+            round_trip("per_user:1234567890:0"),
+            InviteSpec::PerUser(per_user(1234567890, 0, 300))
+        );
+    }
+
+    #[test]
+    fn rejects_invalid_legacy_invites() {
+        for input in [
+            "",
+            "per_user",
+            "per_user:abc",
+            "per_user:1234567890:many",
+            "unknown:1234567890",
+        ] {
+            assert!(
+                InviteSpec::from_legacy(input).is_err(),
+                "{} should be rejected",
+                input
+            );
+        }
+    }
+
+    #[test]
+    fn round_trips_stored_invites() {
+        let mut invite = per_user(1234567890, 1, 0);
+        invite.fallback_channel_ids = vec![ChannelId::new(987654321)];
+        invite.temporary = true;
+
+        for spec in [
+            InviteSpec::Vanity {
+                code: "infinity".to_string(),
+            },
+            InviteSpec::Whitelist(invite.clone()),
+            InviteSpec::Application(ApplicationInvite {
+                invite,
+                review_channel_id: ChannelId::new(555),
+                questions: vec!["Why do you want to join?".to_string()],
+            }),
+        ] {
+            assert_eq!(
+                InviteSpec::from_stored(&spec.to_stored().unwrap()).unwrap(),
+                spec
+            );
+        }
+    }
+
+    #[test]
+    fn rejects_newer_versions() {
+        assert!(InviteSpec::from_stored(r#"{"version":999,"spec":{"None":{}}}"#).is_err());
+    }
+}
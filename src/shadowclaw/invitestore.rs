@@ -0,0 +1,162 @@
+use serenity::all::{ChannelId, GuildId, UserId};
+
+/// How long an invite must still be valid for in order to be reused, in seconds
+///
+/// This avoids handing out invites that expire before the user gets to use them
+const MIN_REMAINING_VALIDITY: i64 = 60;
+
+/// Returns the code of an invite previously created for a user that is still valid, if any
+pub async fn find_reusable_invite(
+    pool: &sqlx::PgPool,
+    guild_id: GuildId,
+    user_id: UserId,
+    channel_id: ChannelId,
+    temporary: bool,
+) -> Result<Option<String>, crate::Error> {
+    let rec = sqlx::query!(
+        "SELECT code FROM server_invites WHERE server_id = $1 AND user_id = $2 AND channel_id = $3 AND temporary = $5 AND NOT deleted
+        AND (expires_at IS NULL OR expires_at > NOW() + make_interval(secs => $4))
+        AND (max_uses = 0 OR uses < max_uses)
+        ORDER BY created_at DESC LIMIT 1",
+        guild_id.to_string(),
+        user_id.to_string(),
+        channel_id.to_string(),
+        MIN_REMAINING_VALIDITY as f64,
+        temporary
+    )
+    .fetch_optional(pool)
+    .await?;
+
+    Ok(rec.map(|r| r.code))
+}
+
+/// Records a newly created per-user invite
+pub async fn record_invite(
+    pool: &sqlx::PgPool,
+    guild_id: GuildId,
+    user_id: Option<UserId>,
+    channel_id: ChannelId,
+    code: &str,
+    max_uses: u8,
+    max_age: u32,
+    temporary: bool,
+) -> Result<(), crate::Error> {
+    let expires_at = if max_age > 0 {
+        Some(chrono::Utc::now() + chrono::Duration::seconds(max_age.into()))
+    } else {
+        None
+    };
+
+    sqlx::query!(
+        "INSERT INTO server_invites (code, server_id, user_id, channel_id, max_uses, expires_at, temporary) VALUES ($1, $2, $3, $4, $5, $6, $7)",
+        code,
+        guild_id.to_string(),
+        user_id.map(|u| u.to_string()),
+        channel_id.to_string(),
+        i32::from(max_uses),
+        expires_at,
+        temporary
+    )
+    .execute(pool)
+    .await?;
+
+    Ok(())
+}
+
+/// Marks an invite as deleted on Discord (either manually or because it ran out of uses)
+pub async fn mark_invite_deleted(pool: &sqlx::PgPool, code: &str) -> Result<(), crate::Error> {
+    sqlx::query!(
+        "UPDATE server_invites SET deleted = true WHERE code = $1",
+        code
+    )
+    .execute(pool)
+    .await?;
+
+    Ok(())
+}
+
+/// Revokes an invite Infinity List created on Discord and marks it as deleted
+///
+/// Invites that Discord no longer knows about are treated as already revoked
+pub async fn revoke_invite(
+    http: &serenity::all::Http,
+    pool: &sqlx::PgPool,
+    code: &str,
+    reason: &str,
+) -> Result<(), crate::Error> {
+    if let Err(e) = http.delete_invite(code, Some(reason)).await {
+        let not_found = match &e {
+            serenity::Error::Http(e) => {
+                e.status_code() == Some(serenity::http::StatusCode::NOT_FOUND)
+            }
+            _ => false,
+        };
+
+        if !not_found {
+            return Err(e.into());
+        }
+    }
+
+    mark_invite_deleted(pool, code).await
+}
+
+/// Revokes the given invites, returning how many were revoked
+async fn revoke_invites(
+    http: &serenity::all::Http,
+    pool: &sqlx::PgPool,
+    codes: Vec<String>,
+    reason: &str,
+) -> usize {
+    let mut revoked = 0;
+
+    for code in codes {
+        match revoke_invite(http, pool, &code, reason).await {
+            Ok(()) => revoked += 1,
+            Err(e) => log::warn!("Failed to revoke invite {}: {}", code, e),
+        }
+    }
+
+    revoked
+}
+
+/// Revokes all outstanding invites created for a user in a server
+pub async fn revoke_user_invites(
+    http: &serenity::all::Http,
+    pool: &sqlx::PgPool,
+    guild_id: GuildId,
+    user_id: UserId,
+    reason: &str,
+) -> Result<usize, crate::Error> {
+    let codes = sqlx::query!(
+        "SELECT code FROM server_invites WHERE server_id = $1 AND user_id = $2 AND NOT deleted",
+        guild_id.to_string(),
+        user_id.to_string()
+    )
+    .fetch_all(pool)
+    .await?
+    .into_iter()
+    .map(|r| r.code)
+    .collect();
+
+    Ok(revoke_invites(http, pool, codes, reason).await)
+}
+
+/// Revokes all outstanding invites created in a server
+pub async fn revoke_server_invites(
+    http: &serenity::all::Http,
+    pool: &sqlx::PgPool,
+    guild_id: GuildId,
+    reason: &str,
+) -> Result<usize, crate::Error> {
+    let codes = sqlx::query!(
+        "SELECT code FROM server_invites WHERE server_id = $1 AND NOT deleted",
+        guild_id.to_string()
+    )
+    .fetch_all(pool)
+    .await?
+    .into_iter()
+    .map(|r| r.code)
+    .collect();
+
+    Ok(revoke_invites(http, pool, codes, reason).await)
+}
@@ -0,0 +1,103 @@
+use serde::{Deserialize, Serialize};
+use serenity::all::GuildId;
+use ts_rs::TS;
+use utoipa::ToSchema;
+
+/// How many days hourly samples are kept for before being downsampled to daily averages
+pub const HOURLY_RETENTION_DAYS: i32 = 7;
+
+/// How many days samples are kept for at all
+pub const RETENTION_DAYS: i32 = 365;
+
+/// A sample of the member counts of a server
+#[derive(Debug, Serialize, Deserialize, ToSchema, TS, Clone)]
+#[ts(export, export_to = ".generated/MemberStatsSample.ts")]
+pub struct MemberStatsSample {
+    pub total_members: i32,
+    pub online_members: i32,
+    /// Whether this is the daily average of older samples
+    pub daily: bool,
+    #[schema(value_type = String)]
+    pub created_at: chrono::DateTime<chrono::Utc>,
+}
+
+/// Records the current member counts of the given listed servers
+pub async fn record_samples(
+    pool: &sqlx::PgPool,
+    guild_ids: &[String],
+) -> Result<u64, crate::Error> {
+    let res = sqlx::query!(
+        "INSERT INTO server_member_stats (server_id, total_members, online_members)
+        SELECT server_id, total_members, online_members FROM servers WHERE server_id = ANY($1)
+        ON CONFLICT DO NOTHING",
+        guild_ids
+    )
+    .execute(pool)
+    .await?;
+
+    Ok(res.rows_affected())
+}
+
+/// Downsamples hourly samples older than ``HOURLY_RETENTION_DAYS`` to daily averages and drops samples older than ``RETENTION_DAYS``
+///
+/// Only whole days are downsampled, so every day is downsampled exactly once
+pub async fn downsample(pool: &sqlx::PgPool) -> Result<(), crate::Error> {
+    let mut tx = pool.begin().await?;
+
+    sqlx::query!(
+        "WITH old AS (
+            DELETE FROM server_member_stats
+            WHERE NOT daily AND created_at < date_trunc('day', NOW() - make_interval(days => $1))
+            RETURNING server_id, created_at, total_members, online_members
+        )
+        INSERT INTO server_member_stats (server_id, created_at, total_members, online_members, daily)
+        SELECT server_id, date_trunc('day', created_at), AVG(total_members)::integer, AVG(online_members)::integer, true
+        FROM old GROUP BY server_id, date_trunc('day', created_at)
+        ON CONFLICT DO NOTHING",
+        HOURLY_RETENTION_DAYS
+    )
+    .execute(&mut *tx)
+    .await?;
+
+    sqlx::query!(
+        "DELETE FROM server_member_stats WHERE created_at < NOW() - make_interval(days => $1)",
+        RETENTION_DAYS
+    )
+    .execute(&mut *tx)
+    .await?;
+
+    tx.commit().await?;
+
+    Ok(())
+}
+
+/// Returns the member count samples of a server from the last ``days`` days, oldest first
+pub async fn get_member_stats(
+    pool: &sqlx::PgPool,
+    guild_id: GuildId,
+    days: i32,
+) -> Result<Vec<MemberStatsSample>, crate::Error> {
+    if !(1..=RETENTION_DAYS).contains(&days) {
+        return Err(format!("Days must be between 1 and {}", RETENTION_DAYS).into());
+    }
+
+    let rows = sqlx::query!(
+        "SELECT total_members, online_members, daily, created_at FROM server_member_stats
+        WHERE server_id = $1 AND created_at > NOW() - make_interval(days => $2)
+        ORDER BY created_at",
+        guild_id.to_string(),
+        days
+    )
+    .fetch_all(pool)
+    .await?;
+
+    Ok(rows
+        .into_iter()
+        .map(|r| MemberStatsSample {
+            total_members: r.total_members,
+            online_members: r.online_members,
+            daily: r.daily,
+            created_at: r.created_at,
+        })
+        .collect())
+}
@@ -1,4 +1,23 @@
+pub mod applications;
+pub mod assets;
+pub mod blacklist;
+pub mod botmissing;
 pub mod invite;
+pub mod invitecode;
+pub mod invitehealth;
+pub mod invitelog;
+pub mod inviterules;
+pub mod inviteschedule;
+pub mod invitespec;
+pub mod invitestore;
+pub mod memberstats;
+pub mod notify;
+pub mod pause;
 pub mod perms;
+pub mod ratelimit;
 pub mod stats;
+pub mod team;
+pub mod tracker;
+pub mod vanity;
 pub mod webp;
+pub mod whitelist;
@@ -0,0 +1,118 @@
+use once_cell::sync::Lazy;
+use serenity::all::{ChannelId, CreateEmbed, CreateMessage, GuildId, UserId};
+use std::collections::HashSet;
+use std::sync::Mutex;
+
+/// Team members with this permission are DM'd when a server has no alert channel
+const NOTIFY_PERMISSION: &str = "server.edit";
+
+/// Keys of notifications that have already been sent, see ``notify_team_once``
+static NOTIFIED: Lazy<Mutex<HashSet<String>>> = Lazy::new(|| Mutex::new(HashSet::new()));
+
+/// Posts an alert to the alert channel of a server
+///
+/// Returns false if the server has no alert channel configured
+pub async fn send_alert(
+    http: &serenity::all::Http,
+    pool: &sqlx::PgPool,
+    guild_id: GuildId,
+    embed: CreateEmbed,
+) -> Result<bool, crate::Error> {
+    let rec = sqlx::query!(
+        "SELECT alert_channel FROM servers WHERE server_id = $1",
+        guild_id.to_string()
+    )
+    .fetch_optional(pool)
+    .await?;
+
+    let Some(alert_channel) = rec.and_then(|r| r.alert_channel) else {
+        return Ok(false);
+    };
+
+    let channel_id = alert_channel.parse::<ChannelId>()?;
+
+    channel_id
+        .send_message(http, CreateMessage::new().embed(embed))
+        .await?;
+
+    Ok(true)
+}
+
+/// Notifies the team of a server, either through the alert channel or by DM'ing the team members with ``server.edit``
+pub async fn notify_team(
+    http: &serenity::all::Http,
+    pool: &sqlx::PgPool,
+    guild_id: GuildId,
+    embed: CreateEmbed,
+) -> Result<(), crate::Error> {
+    if send_alert(http, pool, guild_id, embed.clone()).await? {
+        return Ok(());
+    }
+
+    dm_team(http, pool, guild_id, embed).await
+}
+
+/// DMs the team members of a server with ``server.edit``
+///
+/// Unlike ``notify_team``, this works even if Infinity List is no longer in the server
+pub async fn dm_team(
+    http: &serenity::all::Http,
+    pool: &sqlx::PgPool,
+    guild_id: GuildId,
+    embed: CreateEmbed,
+) -> Result<(), crate::Error> {
+    let members = sqlx::query!(
+        "SELECT team_members.user_id, team_members.flags FROM team_members
+        INNER JOIN servers ON servers.team_owner = team_members.team_id
+        WHERE servers.server_id = $1",
+        guild_id.to_string()
+    )
+    .fetch_all(pool)
+    .await?;
+
+    for member in members {
+        let permissions = super::perms::resolve_team_flags(member.flags);
+
+        if !kittycat::perms::has_perm(&permissions, &NOTIFY_PERMISSION.into()) {
+            continue;
+        }
+
+        let user_id = member.user_id.parse::<UserId>()?;
+
+        if let Err(e) = user_id
+            .direct_message(http, CreateMessage::new().embed(embed.clone()))
+            .await
+        {
+            log::warn!("Failed to DM team member {}: {}", user_id, e);
+        }
+    }
+
+    Ok(())
+}
+
+/// Like ``notify_team``, but only notifies once per ``key`` until ``clear_notified`` is called with it
+pub async fn notify_team_once(
+    http: &serenity::all::Http,
+    pool: &sqlx::PgPool,
+    guild_id: GuildId,
+    key: String,
+    embed: CreateEmbed,
+) -> Result<(), crate::Error> {
+    if !NOTIFIED.lock().unwrap().insert(key.clone()) {
+        return Ok(());
+    }
+
+    let res = notify_team(http, pool, guild_id, embed).await;
+
+    if res.is_err() {
+        // Allow retrying on the next occurrence
+        NOTIFIED.lock().unwrap().remove(&key);
+    }
+
+    res
+}
+
+/// Allows the notification with the given key to be sent again
+pub fn clear_notified(key: &str) {
+    NOTIFIED.lock().unwrap().remove(key);
+}
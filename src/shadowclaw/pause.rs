@@ -0,0 +1,108 @@
+use serenity::all::{CreateEmbed, GuildId};
+
+/// Returns whether invites to a server are paused, and until when (None = until resumed)
+pub async fn get_pause(
+    pool: &sqlx::PgPool,
+    guild_id: GuildId,
+) -> Result<Option<Option<chrono::DateTime<chrono::Utc>>>, crate::Error> {
+    let rec = sqlx::query!(
+        "SELECT invites_paused_until FROM servers WHERE server_id = $1 AND invites_paused = true AND (invites_paused_until IS NULL OR invites_paused_until > NOW())",
+        guild_id.to_string()
+    )
+    .fetch_optional(pool)
+    .await?;
+
+    Ok(rec.map(|r| r.invites_paused_until))
+}
+
+/// Pauses invites to a server until the given time (None = until resumed)
+pub async fn pause_invites(
+    pool: &sqlx::PgPool,
+    guild_id: GuildId,
+    until: Option<chrono::DateTime<chrono::Utc>>,
+) -> Result<(), crate::Error> {
+    let res = sqlx::query!(
+        "UPDATE servers SET invites_paused = true, invites_paused_until = $2 WHERE server_id = $1",
+        guild_id.to_string(),
+        until
+    )
+    .execute(pool)
+    .await?;
+
+    if res.rows_affected() == 0 {
+        return Err("This server is not on Infinity List!".into());
+    }
+
+    Ok(())
+}
+
+/// Resumes invites to a server
+pub async fn resume_invites(pool: &sqlx::PgPool, guild_id: GuildId) -> Result<(), crate::Error> {
+    let res = sqlx::query!(
+        "UPDATE servers SET invites_paused = false, invites_paused_until = NULL WHERE server_id = $1",
+        guild_id.to_string()
+    )
+    .execute(pool)
+    .await?;
+
+    if res.rows_affected() == 0 {
+        return Err("This server is not on Infinity List!".into());
+    }
+
+    Ok(())
+}
+
+/// Pauses invites to a server if the joins attributed to Infinity List invites within the raid window reach the raid threshold
+///
+/// Returns true if invites were paused
+pub async fn check_raid(
+    http: &serenity::all::Http,
+    pool: &sqlx::PgPool,
+    guild_id: GuildId,
+) -> Result<bool, crate::Error> {
+    let rec = sqlx::query!(
+        "SELECT raid_join_threshold, raid_window_secs, raid_pause_minutes,
+        (SELECT COUNT(*) FROM server_invite_joins WHERE server_invite_joins.server_id = servers.server_id AND created_at > NOW() - make_interval(secs => raid_window_secs)) AS joins
+        FROM servers WHERE server_id = $1",
+        guild_id.to_string()
+    )
+    .fetch_optional(pool)
+    .await?;
+
+    let Some(rec) = rec else {
+        return Ok(false);
+    };
+
+    let Some(threshold) = rec.raid_join_threshold else {
+        return Ok(false);
+    };
+
+    if rec.joins.unwrap_or(0) < i64::from(threshold) {
+        return Ok(false);
+    }
+
+    if get_pause(pool, guild_id).await?.is_some() {
+        return Ok(false);
+    }
+
+    let until = chrono::Utc::now() + chrono::Duration::minutes(rec.raid_pause_minutes.into());
+
+    pause_invites(pool, guild_id, Some(until)).await?;
+
+    super::notify::notify_team(
+        http,
+        pool,
+        guild_id,
+        CreateEmbed::new()
+            .title("Invites Paused")
+            .description(format!(
+                "{} members joined through Infinity List within {} seconds, so Infinity List invites to this server have been paused until <t:{}:f>. Use `/invites resume` to resume them earlier.",
+                rec.joins.unwrap_or(0),
+                rec.raid_window_secs,
+                until.timestamp()
+            )),
+    )
+    .await?;
+
+    Ok(true)
+}
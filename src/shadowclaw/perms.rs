@@ -32,37 +32,32 @@ pub async fn get_member_team_permissions(
         return Ok(GetMemberTeamPermissionsResult::MemberNotInTeam);
     };
 
+    Ok(GetMemberTeamPermissionsResult::Found(resolve_team_flags(
+        team_member_perms.flags,
+    )))
+}
+
+/// Resolves the flags of a team member into their permissions
+pub fn resolve_team_flags(flags: Vec<String>) -> Vec<kittycat::perms::Permission> {
     // Right now, team permissions are treated as permission overrides
     // TODO: support hierarchy based permissions in the future
     let sp = kittycat::perms::StaffPermissions {
         user_positions: vec![],
-        perm_overrides: team_member_perms
-            .flags
-            .into_iter()
-            .map(|f| f.into())
-            .collect(),
+        perm_overrides: flags.into_iter().map(|f| f.into()).collect(),
     };
 
-    Ok(GetMemberTeamPermissionsResult::Found(sp.resolve()))
+    sp.resolve()
 }
 
-/// Simple helper method to check for a permission
-pub async fn check_for_permission(
-    ctx: &crate::Context<'_>,
+/// Checks that a user has a permission on the team owning a server
+pub async fn check_permission(
+    pool: &sqlx::PgPool,
+    guild_id: serenity::all::GuildId,
+    user_id: serenity::all::UserId,
     perm: &str,
 ) -> Result<(), crate::Error> {
-    let Some(guild_id) = ctx.guild_id() else {
-        return Err("This operation can only be performed in a server".into());
-    };
-
-    match crate::shadowclaw::perms::get_member_team_permissions(
-        &ctx.data().pool,
-        guild_id,
-        ctx.author().id,
-    )
-    .await?
-    {
-        crate::shadowclaw::perms::GetMemberTeamPermissionsResult::Found(permissions) => {
+    match get_member_team_permissions(pool, guild_id, user_id).await? {
+        GetMemberTeamPermissionsResult::Found(permissions) => {
             if !kittycat::perms::has_perm(&permissions, &perm.into()) {
                 return Err(format!(
                     "You must have the ``{}`` permission to perform this operation!",
@@ -71,13 +66,25 @@ pub async fn check_for_permission(
                 .into());
             }
         }
-        crate::shadowclaw::perms::GetMemberTeamPermissionsResult::ServerNotFound => {
+        GetMemberTeamPermissionsResult::ServerNotFound => {
             return Err("This server is not on Infinity List! Run `/setup` to enlist it!".into());
         }
-        crate::shadowclaw::perms::GetMemberTeamPermissionsResult::MemberNotInTeam => {
+        GetMemberTeamPermissionsResult::MemberNotInTeam => {
             return Err("You are not in this server's team!".into());
         }
     }
 
     Ok(())
 }
+
+/// Simple helper method to check for a permission
+pub async fn check_for_permission(
+    ctx: &crate::Context<'_>,
+    perm: &str,
+) -> Result<(), crate::Error> {
+    let Some(guild_id) = ctx.guild_id() else {
+        return Err("This operation can only be performed in a server".into());
+    };
+
+    check_permission(&ctx.data().pool, guild_id, ctx.author().id, perm).await
+}
@@ -0,0 +1,170 @@
+use crate::config::Ratelimit;
+use once_cell::sync::Lazy;
+use serenity::all::{GuildId, UserId};
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// Once this many buckets exist, full (idle) buckets are pruned
+const MAX_IDLE_BUCKETS: usize = 10000;
+
+#[derive(Hash, PartialEq, Eq, Clone, Copy)]
+enum BucketKey {
+    Global,
+    Guild(GuildId),
+    User(UserId),
+}
+
+struct TokenBucket {
+    tokens: f64,
+    last_refill: Instant,
+}
+
+impl TokenBucket {
+    fn new(limit: &Ratelimit) -> Self {
+        Self {
+            tokens: limit.capacity.into(),
+            last_refill: Instant::now(),
+        }
+    }
+
+    /// Refills the bucket based on the time elapsed since the last refill
+    fn refill(&mut self, limit: &Ratelimit, now: Instant) {
+        let rate = f64::from(limit.capacity) / limit.per_secs.max(1) as f64;
+        let elapsed = now.duration_since(self.last_refill).as_secs_f64();
+
+        self.tokens = (self.tokens + elapsed * rate).min(limit.capacity.into());
+        self.last_refill = now;
+    }
+
+    /// Returns how long until a token is available, or None if one is available now
+    fn retry_after(&self, limit: &Ratelimit) -> Option<Duration> {
+        if self.tokens >= 1.0 {
+            return None;
+        }
+
+        let rate = f64::from(limit.capacity) / limit.per_secs.max(1) as f64;
+
+        Some(Duration::from_secs_f64((1.0 - self.tokens) / rate))
+    }
+}
+
+static BUCKETS: Lazy<Mutex<HashMap<BucketKey, TokenBucket>>> =
+    Lazy::new(|| Mutex::new(HashMap::new()));
+
+/// Checks the global, per-guild and (if logged in) per-user ratelimits for creating an invite
+///
+/// A token is only consumed from the buckets if all of them allow the request. On failure, this returns
+/// how long the caller must wait before retrying
+pub fn check_invite_ratelimit(guild_id: GuildId, user_id: Option<UserId>) -> Result<(), Duration> {
+    let limits = &crate::config::CONFIG.invite_ratelimits;
+
+    let mut keys = vec![
+        (BucketKey::Global, &limits.global),
+        (BucketKey::Guild(guild_id), &limits.per_guild),
+    ];
+
+    if let Some(user_id) = user_id {
+        keys.push((BucketKey::User(user_id), &limits.per_user));
+    }
+
+    let now = Instant::now();
+    let mut buckets = BUCKETS.lock().unwrap();
+
+    if buckets.len() > MAX_IDLE_BUCKETS {
+        buckets.retain(|key, bucket| {
+            let limit = match key {
+                BucketKey::Global => &limits.global,
+                BucketKey::Guild(_) => &limits.per_guild,
+                BucketKey::User(_) => &limits.per_user,
+            };
+
+            bucket.refill(limit, now);
+            bucket.tokens < f64::from(limit.capacity)
+        });
+    }
+
+    let mut retry_after = None;
+
+    for (key, limit) in keys.iter() {
+        let bucket = buckets
+            .entry(*key)
+            .or_insert_with(|| TokenBucket::new(limit));
+
+        bucket.refill(limit, now);
+
+        if let Some(wait) = bucket.retry_after(limit) {
+            retry_after = Some(retry_after.map_or(wait, |r: Duration| r.max(wait)));
+        }
+    }
+
+    if let Some(retry_after) = retry_after {
+        return Err(retry_after);
+    }
+
+    for (key, _) in keys.iter() {
+        if let Some(bucket) = buckets.get_mut(key) {
+            bucket.tokens -= 1.0;
+        }
+    }
+
+    Ok(())
+}
+
+/// Counts an invite against the daily invite quota of a server, if any
+///
+/// This is atomic, so concurrent callers can't exceed the quota. If the quota has been exceeded, nothing is counted
+/// and this returns how long the caller must wait before the quota resets
+pub async fn consume_daily_quota(
+    pool: &sqlx::PgPool,
+    guild_id: GuildId,
+) -> Result<Option<Duration>, crate::Error> {
+    let rec = sqlx::query!(
+        "SELECT daily_invite_quota FROM servers WHERE server_id = $1",
+        guild_id.to_string()
+    )
+    .fetch_optional(pool)
+    .await?;
+
+    let Some(quota) = rec.and_then(|r| r.daily_invite_quota) else {
+        return Ok(None);
+    };
+
+    let counted = sqlx::query!(
+        "INSERT INTO server_invite_quotas (server_id, day, count) SELECT $1, (NOW() AT TIME ZONE 'UTC')::date, 1 WHERE $2 > 0
+        ON CONFLICT (server_id, day) DO UPDATE SET count = server_invite_quotas.count + 1 WHERE server_invite_quotas.count < $2
+        RETURNING count",
+        guild_id.to_string(),
+        quota
+    )
+    .fetch_optional(pool)
+    .await?;
+
+    if counted.is_some() {
+        return Ok(None);
+    }
+
+    // Quotas reset at midnight UTC
+    let now = chrono::Utc::now();
+    let tomorrow = (now.date_naive() + chrono::Duration::days(1))
+        .and_hms_opt(0, 0, 0)
+        .ok_or("Failed to compute quota reset time")?
+        .and_utc();
+
+    Ok(Some((tomorrow - now).to_std().unwrap_or_default()))
+}
+
+/// Gives back an invite counted by ``consume_daily_quota`` that ended up not being created
+pub async fn release_daily_quota(
+    pool: &sqlx::PgPool,
+    guild_id: GuildId,
+) -> Result<(), crate::Error> {
+    sqlx::query!(
+        "UPDATE server_invite_quotas SET count = count - 1 WHERE server_id = $1 AND day = (NOW() AT TIME ZONE 'UTC')::date AND count > 0",
+        guild_id.to_string()
+    )
+    .execute(pool)
+    .await?;
+
+    Ok(())
+}
@@ -16,20 +16,35 @@ impl GuildStats {
     pub fn from_ctx(ctx: &Context) -> Result<Self, Error> {
         let guild = ctx.guild().ok_or("No guild")?;
 
-        Ok(GuildStats {
+        Ok(Self::from_guild(&guild))
+    }
+
+    pub fn from_guild(guild: &serenity::all::Guild) -> Self {
+        GuildStats {
             name: guild.name.to_string(),
             icon: guild
                 .icon_url()
                 .unwrap_or_else(|| "https://cdn.discordapp.com/embed/avatars/0.png".to_string()),
             owner: guild.owner_id,
-            total_members: guild.members.len(),
+            total_members: usize::try_from(guild.member_count).unwrap_or(guild.members.len()),
             online_members: guild
                 .presences
                 .iter()
                 .filter(|p| p.status != serenity::model::prelude::OnlineStatus::Offline)
                 .count(),
             nsfw: matches!(guild.nsfw_level, serenity::all::NsfwLevel::Explicit),
-        })
+        }
+    }
+
+    /// Returns whether the members and presences of a guild are fully cached, and hence whether the counts
+    /// from ``from_guild`` are exact
+    pub fn is_fully_cached(
+        guild: &serenity::all::Guild,
+        intents: serenity::all::GatewayIntents,
+    ) -> bool {
+        intents.contains(serenity::all::GatewayIntents::GUILD_MEMBERS)
+            && intents.contains(serenity::all::GatewayIntents::GUILD_PRESENCES)
+            && guild.members.len() as u64 >= guild.member_count
     }
 
     pub async fn download_image(&self) -> Result<Vec<u8>, Error> {
@@ -0,0 +1,108 @@
+use serenity::all::{GuildId, UserId};
+
+/// Adds a member who became an administrator to the team of a server, with the same flags ``/setup`` gives administrators
+///
+/// Does nothing unless the server opted into ``auto_promote_admins``, or if the member was promoted before and has since
+/// been removed from the team explicitly. Returns whether the member was added
+pub async fn promote_admin(
+    pool: &sqlx::PgPool,
+    guild_id: GuildId,
+    user_id: UserId,
+) -> Result<bool, crate::Error> {
+    let Some(server) = sqlx::query!(
+        "SELECT team_owner, auto_promote_admins FROM servers WHERE server_id = $1",
+        guild_id.to_string()
+    )
+    .fetch_optional(pool)
+    .await?
+    else {
+        return Ok(false);
+    };
+
+    if !server.auto_promote_admins {
+        return Ok(false);
+    }
+
+    let mut tx = pool.begin().await?;
+
+    let existing = sqlx::query!(
+        "SELECT COUNT(*) FROM team_members WHERE team_id = $1 AND user_id = $2",
+        server.team_owner,
+        user_id.to_string()
+    )
+    .fetch_one(&mut *tx)
+    .await?;
+
+    if existing.count.unwrap_or(0) > 0 {
+        return Ok(false);
+    }
+
+    let promoted = sqlx::query!(
+        "SELECT COUNT(*) FROM server_team_promotions WHERE server_id = $1 AND user_id = $2",
+        guild_id.to_string(),
+        user_id.to_string()
+    )
+    .fetch_one(&mut *tx)
+    .await?;
+
+    if promoted.count.unwrap_or(0) > 0 {
+        return Ok(false);
+    }
+
+    // First ensure the user is a ibl user
+    let res = sqlx::query!(
+        "SELECT COUNT(*) FROM users WHERE user_id = $1",
+        user_id.to_string()
+    )
+    .fetch_one(&mut *tx)
+    .await?;
+
+    if res.count.unwrap_or(0) == 0 {
+        sqlx::query!(
+            "INSERT INTO users (user_id, extra_links, developer, certified) VALUES ($1, $2, false, false)",
+            user_id.to_string(),
+            sqlx::types::JsonValue::Array(vec![]),
+        )
+        .execute(&mut *tx)
+        .await?;
+    }
+
+    sqlx::query!(
+        "INSERT INTO team_members (team_id, user_id, flags, service) VALUES ($1, $2, $3, 'infernoplex')",
+        server.team_owner,
+        user_id.to_string(),
+        &["server.*".to_string()]
+    )
+    .execute(&mut *tx)
+    .await?;
+
+    sqlx::query!(
+        "INSERT INTO server_team_promotions (server_id, user_id) VALUES ($1, $2)",
+        guild_id.to_string(),
+        user_id.to_string()
+    )
+    .execute(&mut *tx)
+    .await?;
+
+    tx.commit().await?;
+
+    Ok(true)
+}
+
+/// Forgets that a member was promoted automatically, so they are promoted again if they become an administrator
+/// again. Used when Infinity List removes them itself, as opposed to someone removing them from the team
+pub async fn forget_promotion(
+    pool: &sqlx::PgPool,
+    guild_id: GuildId,
+    user_id: UserId,
+) -> Result<(), crate::Error> {
+    sqlx::query!(
+        "DELETE FROM server_team_promotions WHERE server_id = $1 AND user_id = $2",
+        guild_id.to_string(),
+        user_id.to_string()
+    )
+    .execute(pool)
+    .await?;
+
+    Ok(())
+}
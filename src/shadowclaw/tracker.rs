@@ -0,0 +1,288 @@
+use super::invitespec::InviteSpec;
+use once_cell::sync::Lazy;
+use serenity::all::{CreateEmbed, GuildId, Mentionable, UserId};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+/// Invite code -> number of uses
+type InviteUses = HashMap<String, u64>;
+
+/// The last known invite uses of every listed guild
+///
+/// Each guild has its own lock so that concurrent joins to the same guild are diffed one at a time
+static INVITE_USES: Lazy<Mutex<HashMap<GuildId, Arc<tokio::sync::Mutex<InviteUses>>>>> =
+    Lazy::new(|| Mutex::new(HashMap::new()));
+
+fn guild_invite_uses(guild_id: GuildId) -> Arc<tokio::sync::Mutex<InviteUses>> {
+    INVITE_USES
+        .lock()
+        .unwrap()
+        .entry(guild_id)
+        .or_default()
+        .clone()
+}
+
+/// A join that was attributed to an Infinity List invite
+pub struct Attribution {
+    pub code: String,
+    /// ``issued`` for invites infernoplex created, ``invite_url`` for the servers fixed invite
+    pub source: &'static str,
+    /// The user the invite was created for, if any
+    pub invite_user_id: Option<UserId>,
+    /// Whether the invite was created for someone other than the member who joined with it
+    pub mismatch: bool,
+}
+
+async fn fetch_invite_uses(
+    http: &serenity::all::Http,
+    guild_id: GuildId,
+) -> Result<InviteUses, crate::Error> {
+    Ok(guild_id
+        .invites(http)
+        .await?
+        .into_iter()
+        .map(|i| (i.code.to_string(), i.uses))
+        .collect())
+}
+
+/// Takes a snapshot of the invite uses of a guild, if it is listed on Infinity List
+pub async fn snapshot_guild(
+    http: &serenity::all::Http,
+    pool: &sqlx::PgPool,
+    guild_id: GuildId,
+) -> Result<(), crate::Error> {
+    let count = sqlx::query!(
+        "SELECT COUNT(*) FROM servers WHERE server_id = $1",
+        guild_id.to_string()
+    )
+    .fetch_one(pool)
+    .await?;
+
+    if count.count.unwrap_or(0) == 0 {
+        return Ok(());
+    }
+
+    let uses = guild_invite_uses(guild_id);
+    let mut uses = uses.lock().await;
+    *uses = fetch_invite_uses(http, guild_id).await?;
+
+    Ok(())
+}
+
+/// Starts tracking a newly created invite
+///
+/// Single-use invites are deleted as soon as they are used, so they must be known beforehand for the join to be attributed
+pub async fn track_invite(guild_id: GuildId, code: &str) {
+    let uses = guild_invite_uses(guild_id);
+    uses.lock().await.entry(code.to_string()).or_insert(0);
+}
+
+/// Works out which invite a new member joined with and stores the attribution if it is an Infinity List invite
+pub async fn attribute_join(
+    http: &serenity::all::Http,
+    pool: &sqlx::PgPool,
+    guild_id: GuildId,
+    user_id: UserId,
+) -> Result<Option<Attribution>, crate::Error> {
+    let Some(server) = sqlx::query!(
+        "SELECT invite FROM servers WHERE server_id = $1",
+        guild_id.to_string()
+    )
+    .fetch_optional(pool)
+    .await?
+    else {
+        return Ok(None);
+    };
+
+    let (increased, vanished) = {
+        let uses = guild_invite_uses(guild_id);
+        let mut uses = uses.lock().await;
+
+        let current = fetch_invite_uses(http, guild_id).await?;
+
+        let increased = current
+            .iter()
+            .filter(|(code, count)| **count > uses.get(*code).copied().unwrap_or(0))
+            .map(|(code, _)| code.clone())
+            .collect::<Vec<_>>();
+
+        let vanished = uses
+            .keys()
+            .filter(|code| !current.contains_key(*code))
+            .cloned()
+            .collect::<Vec<_>>();
+
+        *uses = current;
+
+        (increased, vanished)
+    };
+
+    let fixed_code = match InviteSpec::from_stored(&server.invite) {
+        Ok(InviteSpec::InviteUrl { url }) => super::invitecode::parse_invite_code(&url),
+        _ => None,
+    };
+
+    // Invites that were deleted since the last snapshot can only have been used if they were ours (single-use per-user invites)
+    let issued = sqlx::query!(
+        "SELECT code, user_id FROM server_invites WHERE server_id = $1 AND code = ANY($2)",
+        guild_id.to_string(),
+        &increased
+            .iter()
+            .chain(vanished.iter())
+            .cloned()
+            .collect::<Vec<_>>()
+    )
+    .fetch_all(pool)
+    .await?;
+
+    let attribution = if increased.len() == 1 {
+        let code = &increased[0];
+
+        if let Some(issued) = issued.iter().find(|i| &i.code == code) {
+            Some(Attribution {
+                code: code.clone(),
+                source: "issued",
+                invite_user_id: issued.user_id.as_ref().and_then(|u| u.parse().ok()),
+                mismatch: false,
+            })
+        } else if fixed_code.as_ref() == Some(code) {
+            Some(Attribution {
+                code: code.clone(),
+                source: "invite_url",
+                invite_user_id: None,
+                mismatch: false,
+            })
+        } else {
+            None
+        }
+    } else if increased.is_empty() {
+        let vanished_issued = issued
+            .iter()
+            .filter(|i| vanished.contains(&i.code))
+            .collect::<Vec<_>>();
+
+        if vanished_issued.len() == 1 {
+            Some(Attribution {
+                code: vanished_issued[0].code.clone(),
+                source: "issued",
+                invite_user_id: vanished_issued[0]
+                    .user_id
+                    .as_ref()
+                    .and_then(|u| u.parse().ok()),
+                mismatch: false,
+            })
+        } else {
+            None
+        }
+    } else {
+        // Multiple invites were used since the last snapshot, this join cannot be attributed reliably
+        None
+    };
+
+    let Some(mut attribution) = attribution else {
+        return Ok(None);
+    };
+
+    attribution.mismatch = attribution
+        .invite_user_id
+        .is_some_and(|invite_user_id| invite_user_id != user_id);
+
+    let mut tx = pool.begin().await?;
+
+    sqlx::query!(
+        "INSERT INTO server_invite_joins (server_id, user_id, code, source, invite_user_id, mismatch) VALUES ($1, $2, $3, $4, $5, $6)",
+        guild_id.to_string(),
+        user_id.to_string(),
+        attribution.code,
+        attribution.source,
+        attribution.invite_user_id.map(|u| u.to_string()),
+        attribution.mismatch
+    )
+    .execute(&mut *tx)
+    .await?;
+
+    sqlx::query!(
+        "UPDATE server_invites SET uses = uses + 1 WHERE code = $1",
+        attribution.code
+    )
+    .execute(&mut *tx)
+    .await?;
+
+    tx.commit().await?;
+
+    Ok(Some(attribution))
+}
+
+/// Applies the servers ``invite_mismatch_action`` to a member who joined using a per-user invite created for someone else
+pub async fn handle_invite_mismatch(
+    http: &serenity::all::Http,
+    pool: &sqlx::PgPool,
+    guild_id: GuildId,
+    user_id: UserId,
+    attribution: &Attribution,
+) -> Result<(), crate::Error> {
+    let Some(invite_user_id) = attribution.invite_user_id else {
+        return Ok(());
+    };
+
+    let rec = sqlx::query!(
+        "SELECT invite_mismatch_action FROM servers WHERE server_id = $1",
+        guild_id.to_string()
+    )
+    .fetch_one(pool)
+    .await?;
+
+    let kicked = match rec.invite_mismatch_action.as_str() {
+        "kick" => {
+            http.kick_member(
+                guild_id,
+                user_id,
+                Some(&format!(
+                    "Joined using an Infinity List invite created for {}",
+                    invite_user_id
+                )),
+            )
+            .await?;
+            true
+        }
+        "alert" => false,
+        _ => return Ok(()),
+    };
+
+    super::notify::send_alert(
+        http,
+        pool,
+        guild_id,
+        CreateEmbed::new()
+            .title("Invite Used By Someone Else")
+            .description(format!(
+                "{} ({}) joined using invite `{}` which was created for {} ({}){}",
+                user_id.mention(),
+                user_id,
+                attribution.code,
+                invite_user_id.mention(),
+                invite_user_id,
+                if kicked { " and has been kicked" } else { "" }
+            )),
+    )
+    .await?;
+
+    Ok(())
+}
+
+/// Returns the number of joins attributed to Infinity List invites in the last ``days`` days (or all time if None)
+pub async fn count_attributed_joins(
+    pool: &sqlx::PgPool,
+    guild_id: GuildId,
+    days: Option<i32>,
+) -> Result<i64, crate::Error> {
+    let rec = sqlx::query!(
+        "SELECT COUNT(*) FROM server_invite_joins WHERE server_id = $1 AND ($2::integer IS NULL OR created_at > NOW() - make_interval(days => $2))",
+        guild_id.to_string(),
+        days
+    )
+    .fetch_one(pool)
+    .await?;
+
+    Ok(rec.count.unwrap_or(0))
+}
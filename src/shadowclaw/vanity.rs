@@ -0,0 +1,88 @@
+use super::invitespec::InviteSpec;
+use serenity::all::GuildId;
+
+/// Fetches the vanity invite code of a server from Discord
+///
+/// This needs the Manage Server permission
+pub async fn fetch_vanity_code(
+    http: &serenity::all::Http,
+    guild_id: GuildId,
+) -> Result<String, crate::Error> {
+    let code = guild_id.vanity_url(http).await.map_err(|e| {
+        format!(
+            "Could not fetch the vanity URL of this server, make sure it has one and that Infinity List has the Manage Server permission: {}",
+            e
+        )
+    })?;
+
+    if code.is_empty() {
+        return Err("This server does not have a vanity URL".into());
+    }
+
+    Ok(code)
+}
+
+/// Keeps the invite of a server in vanity mode in sync with its current vanity code
+///
+/// ``vanity_code`` is the vanity code Discord reported in a guild update. If the server lost its vanity, the
+/// invite is marked as broken (notifying the servers team) and no longer handed out
+pub async fn sync_vanity(
+    http: &serenity::all::Http,
+    pool: &sqlx::PgPool,
+    guild_id: GuildId,
+    vanity_code: Option<&str>,
+) -> Result<(), crate::Error> {
+    let Some(row) = sqlx::query!(
+        "SELECT invite FROM servers WHERE server_id = $1",
+        guild_id.to_string()
+    )
+    .fetch_optional(pool)
+    .await?
+    else {
+        return Ok(());
+    };
+
+    let Ok(InviteSpec::Vanity { code }) = InviteSpec::from_stored(&row.invite) else {
+        return Ok(());
+    };
+
+    match vanity_code {
+        Some(new_code) if new_code == code => Ok(()),
+        Some(new_code) => {
+            log::info!(
+                "Vanity of {} changed from {} to {}, syncing invite",
+                guild_id,
+                code,
+                new_code
+            );
+
+            sqlx::query!(
+                "UPDATE servers SET invite = $2 WHERE server_id = $1",
+                guild_id.to_string(),
+                InviteSpec::Vanity {
+                    code: new_code.to_string()
+                }
+                .to_stored()?
+            )
+            .execute(pool)
+            .await?;
+
+            super::invitehealth::record_invite_health(http, pool, guild_id, Ok(())).await
+        }
+        None => {
+            log::warn!("{} lost its vanity {}", guild_id, code);
+
+            super::invitehealth::record_invite_health(
+                http,
+                pool,
+                guild_id,
+                Err(format!(
+                    "This server no longer has its vanity URL (discord.gg/{})",
+                    code
+                )
+                .into()),
+            )
+            .await
+        }
+    }
+}
@@ -0,0 +1,79 @@
+use serenity::all::{GuildId, UserId};
+
+/// The maximum number of users that can be on the invite whitelist of a server
+pub const MAX_WHITELISTED_USERS: usize = 1000;
+
+/// Returns the users on the invite whitelist of a server
+pub async fn get_whitelist(
+    pool: &sqlx::PgPool,
+    guild_id: GuildId,
+) -> Result<Vec<String>, crate::Error> {
+    let row = sqlx::query!(
+        "SELECT whitelisted_users FROM servers WHERE server_id = $1",
+        guild_id.to_string()
+    )
+    .fetch_optional(pool)
+    .await?;
+
+    let Some(row) = row else {
+        return Err("This server is not on Infinity List!".into());
+    };
+
+    Ok(row.whitelisted_users)
+}
+
+/// Adds a user to the invite whitelist of a server, returning the new whitelist
+pub async fn add_to_whitelist(
+    pool: &sqlx::PgPool,
+    guild_id: GuildId,
+    user_id: UserId,
+) -> Result<Vec<String>, crate::Error> {
+    let row = sqlx::query!(
+        "UPDATE servers SET whitelisted_users = array_append(whitelisted_users, $2) WHERE server_id = $1 AND NOT ($2 = ANY(whitelisted_users)) AND cardinality(whitelisted_users) < $3 RETURNING whitelisted_users",
+        guild_id.to_string(),
+        user_id.to_string(),
+        MAX_WHITELISTED_USERS as i32
+    )
+    .fetch_optional(pool)
+    .await?;
+
+    if let Some(row) = row {
+        return Ok(row.whitelisted_users);
+    }
+
+    // Nothing was updated, find out why
+    let whitelist = get_whitelist(pool, guild_id).await?;
+
+    if whitelist.contains(&user_id.to_string()) {
+        Err("This user is already whitelisted".into())
+    } else {
+        Err(format!(
+            "The whitelist cannot have more than {} users",
+            MAX_WHITELISTED_USERS
+        )
+        .into())
+    }
+}
+
+/// Removes a user from the invite whitelist of a server, returning the new whitelist
+pub async fn remove_from_whitelist(
+    pool: &sqlx::PgPool,
+    guild_id: GuildId,
+    user_id: UserId,
+) -> Result<Vec<String>, crate::Error> {
+    let whitelist = get_whitelist(pool, guild_id).await?;
+
+    if !whitelist.contains(&user_id.to_string()) {
+        return Err("This user is not whitelisted".into());
+    }
+
+    let row = sqlx::query!(
+        "UPDATE servers SET whitelisted_users = array_remove(whitelisted_users, $2) WHERE server_id = $1 RETURNING whitelisted_users",
+        guild_id.to_string(),
+        user_id.to_string()
+    )
+    .fetch_one(pool)
+    .await?;
+
+    Ok(row.whitelisted_users)
+}
@@ -1,3 +1,4 @@
+use axum::http::{HeaderMap, StatusCode};
 use sqlx::PgPool;
 
 /// Represents a session that can be used to authorize/identify a user
@@ -37,3 +38,49 @@ impl Session {
         Ok(session)
     }
 }
+
+/// Resolves a session token to the user it belongs to
+///
+/// On failure, this returns the status code, headers and error message that should be sent back
+pub async fn user_from_session(
+    pool: &PgPool,
+    token: &str,
+) -> Result<serenity::all::UserId, (StatusCode, HeaderMap, String)> {
+    let session = Session::from_token(pool, token).await.map_err(|e| {
+        (
+            StatusCode::BAD_REQUEST,
+            HeaderMap::new(),
+            format!("Invalid session: {}", e),
+        )
+    })?;
+
+    let Some(session) = session else {
+        let mut headers = HeaderMap::new();
+        headers.insert("X-Session-Invalid", "1".parse().unwrap());
+        return Err((
+            StatusCode::FORBIDDEN,
+            headers,
+            "Invalid session token".to_string(),
+        ));
+    };
+
+    // Check that target_type == 'user'
+    if session.target_type != "user" {
+        return Err((
+            StatusCode::FORBIDDEN,
+            HeaderMap::new(),
+            "This query can only be called on a user session".to_string(),
+        ));
+    }
+
+    session
+        .target_id
+        .parse::<serenity::all::UserId>()
+        .map_err(|e| {
+            (
+                StatusCode::FORBIDDEN,
+                HeaderMap::new(),
+                format!("Invalid user ID: {}", e),
+            )
+        })
+}
@@ -1,6 +1,11 @@
 use std::{str::FromStr, sync::Arc};
 
+use crate::shadowclaw::blacklist::BlacklistEntry;
 use crate::shadowclaw::invite::{CreateInviteForUserError, CreateInviteForUserResult};
+use crate::shadowclaw::invitelog::InviteLogEntry;
+use crate::shadowclaw::inviterules::InviteRule;
+use crate::shadowclaw::inviteschedule::{InviteSchedule, InviteWindow};
+use crate::shadowclaw::memberstats::MemberStatsSample;
 use axum::extract::DefaultBodyLimit;
 use axum::http::{HeaderMap, HeaderName};
 use axum::{
@@ -42,7 +47,16 @@ pub async fn setup_server(
             InfernoplexResponse,
             InfernoplexError,
             CreateInviteForUserResult,
-            CreateInviteForUserError
+            CreateInviteForUserError,
+            WhitelistAction,
+            InviteLogEntry,
+            BlacklistAction,
+            BlacklistEntry,
+            InviteRule,
+            InviteScheduleAction,
+            InviteSchedule,
+            InviteWindow,
+            MemberStatsSample
         ))
     )]
     struct ApiDoc;
@@ -79,7 +93,10 @@ pub async fn setup_server(
                 .allow_origin(Any)
                 .allow_methods(Any)
                 .allow_headers(Any)
-                .expose_headers([HeaderName::from_str("X-Session-Invalid").unwrap()]),
+                .expose_headers([
+                    HeaderName::from_str("X-Session-Invalid").unwrap(),
+                    HeaderName::from_str("Retry-After").unwrap(),
+                ]),
         );
 
     let addr = format!("127.0.0.1:{}", crate::config::CONFIG.server_port.get());
@@ -103,10 +120,14 @@ pub enum InfernoplexQuery {
     /// be optionally passed for login.
     ///
     /// This returns a ``Result<CreateInviteForUserResult, CreateInviteForUserError>``
+    ///
+    /// For servers that review join applications, ``answers`` must contain the answers to
+    /// the servers questions (see ``CreateInviteForUserError::ApplicationAnswersRequired``)
     CreateInvite {
         session: Option<String>,
         #[ts(type = "string")]
         guild_id: serenity::all::GuildId,
+        answers: Option<Vec<String>>,
     },
     /// Resolves an invite
     ResolveInvite {
@@ -114,6 +135,112 @@ pub enum InfernoplexQuery {
         #[ts(type = "string")]
         guild_id: serenity::all::GuildId,
     },
+    /// Views or edits the invite whitelist of a server
+    ///
+    /// Needs the ``server.whitelist`` permission on the servers team
+    ManageWhitelist {
+        session: String,
+        #[ts(type = "string")]
+        guild_id: serenity::all::GuildId,
+        action: WhitelistAction,
+    },
+    /// Returns the invites handed out for a server, newest first
+    ///
+    /// Needs the ``server.view_invite_logs`` permission on the servers team
+    GetInviteLogs {
+        session: String,
+        #[ts(type = "string")]
+        guild_id: serenity::all::GuildId,
+        limit: Option<i64>,
+        offset: Option<i64>,
+    },
+    /// Views or edits the invite blacklist of a server
+    ///
+    /// Needs the ``server.blacklist`` permission on the servers team
+    ManageBlacklist {
+        session: String,
+        #[ts(type = "string")]
+        guild_id: serenity::all::GuildId,
+        action: BlacklistAction,
+    },
+    /// Returns the invite rules of a server, replacing them with ``rules`` first if set
+    ///
+    /// Needs the ``server.edit`` permission on the servers team
+    ManageInviteRules {
+        session: String,
+        #[ts(type = "string")]
+        guild_id: serenity::all::GuildId,
+        rules: Option<Vec<InviteRule>>,
+    },
+    /// Views or edits the weekly schedule in which a server hands out invites
+    ///
+    /// Needs the ``server.edit`` permission on the servers team
+    ManageInviteSchedule {
+        session: String,
+        #[ts(type = "string")]
+        guild_id: serenity::all::GuildId,
+        action: InviteScheduleAction,
+    },
+    /// Returns the member count history of a server, oldest first
+    ///
+    /// Samples are hourly for the last week and daily averages before that. ``days`` defaults to 30
+    GetServerStats {
+        #[ts(type = "string")]
+        guild_id: serenity::all::GuildId,
+        days: Option<i32>,
+    },
+}
+
+/// An action to perform on the invite whitelist of a server
+#[derive(Serialize, Deserialize, ToSchema, TS, Clone)]
+#[ts(export, export_to = ".generated/WhitelistAction.ts")]
+pub enum WhitelistAction {
+    /// Lists all whitelisted users
+    List {},
+    /// Adds a user to the whitelist
+    Add {
+        #[ts(type = "string")]
+        user_id: serenity::all::UserId,
+    },
+    /// Removes a user from the whitelist
+    Remove {
+        #[ts(type = "string")]
+        user_id: serenity::all::UserId,
+    },
+}
+
+/// An action to perform on the invite blacklist of a server
+#[derive(Serialize, Deserialize, ToSchema, TS, Clone)]
+#[ts(export, export_to = ".generated/BlacklistAction.ts")]
+pub enum BlacklistAction {
+    /// Lists all blacklisted users
+    List {},
+    /// Adds a user to the blacklist
+    Add {
+        #[ts(type = "string")]
+        user_id: serenity::all::UserId,
+        reason: Option<String>,
+        /// When the entry should expire, None for a permanent blacklist
+        #[schema(value_type = Option<String>)]
+        expires_at: Option<chrono::DateTime<chrono::Utc>>,
+    },
+    /// Removes a user from the blacklist
+    Remove {
+        #[ts(type = "string")]
+        user_id: serenity::all::UserId,
+    },
+}
+
+/// An action to perform on the invite schedule of a server
+#[derive(Serialize, Deserialize, ToSchema, TS, Clone)]
+#[ts(export, export_to = ".generated/InviteScheduleAction.ts")]
+pub enum InviteScheduleAction {
+    /// Returns the current schedule
+    Get {},
+    /// Replaces the schedule
+    Set { schedule: InviteSchedule },
+    /// Removes the schedule, handing out invites at all times
+    Clear {},
 }
 
 #[derive(Serialize, Deserialize, ToSchema, TS, Display, Clone, VariantNames)]
@@ -125,6 +252,33 @@ pub enum InfernoplexResponse {
         result: CreateInviteForUserResult,
     },
     ResolveInvite {},
+    /// The result of calling ManageWhitelist
+    ManageWhitelist {
+        /// The whitelisted users (after performing the action)
+        users: Vec<String>,
+    },
+    /// The result of calling GetInviteLogs
+    GetInviteLogs {
+        logs: Vec<InviteLogEntry>,
+    },
+    /// The result of calling ManageBlacklist
+    ManageBlacklist {
+        /// The blacklist (after performing the action)
+        entries: Vec<BlacklistEntry>,
+    },
+    /// The result of calling ManageInviteRules
+    ManageInviteRules {
+        rules: Vec<InviteRule>,
+    },
+    /// The result of calling ManageInviteSchedule
+    ManageInviteSchedule {
+        /// The schedule (after performing the action), None if invites are handed out at all times
+        schedule: Option<InviteSchedule>,
+    },
+    /// The result of calling GetServerStats
+    GetServerStats {
+        samples: Vec<MemberStatsSample>,
+    },
 }
 
 impl IntoResponse for InfernoplexResponse {
@@ -148,6 +302,36 @@ pub enum InfernoplexError {
         /// The error that occured
         message: String,
     },
+    /// The result of calling ManageWhitelist
+    ManageWhitelist {
+        /// The error that occured
+        message: String,
+    },
+    /// The result of calling GetInviteLogs
+    GetInviteLogs {
+        /// The error that occured
+        message: String,
+    },
+    /// The result of calling ManageBlacklist
+    ManageBlacklist {
+        /// The error that occured
+        message: String,
+    },
+    /// The result of calling ManageInviteRules
+    ManageInviteRules {
+        /// The error that occured
+        message: String,
+    },
+    /// The result of calling ManageInviteSchedule
+    ManageInviteSchedule {
+        /// The error that occured
+        message: String,
+    },
+    /// The result of calling GetServerStats
+    GetServerStats {
+        /// The error that occured
+        message: String,
+    },
 }
 
 #[derive(Clone)]
@@ -173,6 +357,45 @@ impl IntoResponse for InfernoplexErrorResponse {
     }
 }
 
+/// Converts an error from creating an invite to an error response with the appropriate status code
+fn create_invite_error(e: CreateInviteForUserError) -> InfernoplexErrorResponse {
+    let mut headers = HeaderMap::new();
+
+    let status = match e {
+        CreateInviteForUserError::RateLimited { retry_after }
+        | CreateInviteForUserError::DailyQuotaExceeded { retry_after } => {
+            headers.insert("Retry-After", retry_after.into());
+            StatusCode::TOO_MANY_REQUESTS
+        }
+        CreateInviteForUserError::InvitesPaused { until } => {
+            if let Some(until) = until {
+                let retry_after = (until - chrono::Utc::now()).num_seconds().max(0) as u64;
+                headers.insert("Retry-After", retry_after.into());
+            }
+
+            StatusCode::SERVICE_UNAVAILABLE
+        }
+        CreateInviteForUserError::OutsideInviteWindow { next_open } => {
+            if let Some(next_open) = next_open {
+                let retry_after = (next_open - chrono::Utc::now()).num_seconds().max(0) as u64;
+                headers.insert("Retry-After", retry_after.into());
+            }
+
+            StatusCode::SERVICE_UNAVAILABLE
+        }
+        _ => StatusCode::INTERNAL_SERVER_ERROR,
+    };
+
+    InfernoplexErrorResponse::new(
+        status,
+        headers,
+        InfernoplexError::CreateInvite {
+            err: e.clone(),
+            message: e.to_string(),
+        },
+    )
+}
+
 /// Make Infernoplex Query
 #[utoipa::path(
     post,
@@ -181,6 +404,8 @@ impl IntoResponse for InfernoplexErrorResponse {
     responses(
         (status = 200, description = "The response of the query", body = InfernoplexResponse),
         (status = BAD_REQUEST, description = "An error occured performing the requested action", body = InfernoplexError),
+        (status = TOO_MANY_REQUESTS, description = "The caller is being ratelimited, see the Retry-After header", body = InfernoplexError),
+        (status = SERVICE_UNAVAILABLE, description = "Invites to the server are paused or outside its invite schedule, see the Retry-After header (if any)", body = InfernoplexError),
     ),
 )]
 #[axum::debug_handler]
@@ -189,81 +414,47 @@ async fn query(
     Json(req): Json<InfernoplexQuery>,
 ) -> Result<InfernoplexResponse, InfernoplexErrorResponse> {
     match req {
-        InfernoplexQuery::CreateInvite { guild_id, session } => {
+        InfernoplexQuery::CreateInvite {
+            guild_id,
+            session,
+            answers,
+        } => {
             let user_id = if let Some(session) = session {
-                let auth_session = super::auth::Session::from_token(&state.pool, &session)
+                let user_id = super::auth::user_from_session(&state.pool, &session)
                     .await
-                    .map_err(|e| {
+                    .map_err(|(status, headers, message)| {
                         InfernoplexErrorResponse::new(
-                            StatusCode::BAD_REQUEST,
-                            HeaderMap::new(),
+                            status,
+                            headers,
                             InfernoplexError::CreateInvite {
                                 err: CreateInviteForUserError::Generic {
-                                    message: format!("Invalid session: {}", e),
+                                    message: message.clone(),
                                 },
-                                message: format!("Invalid session: {}", e),
+                                message,
                             },
                         )
                     })?;
 
-                if let Some(auth_session) = auth_session {
-                    // Check that target_type == 'user'
-                    if auth_session.target_type != "user" {
-                        return Err(InfernoplexErrorResponse::new(
-                            StatusCode::FORBIDDEN,
-                            HeaderMap::new(),
-                            InfernoplexError::CreateInvite {
-                                err: CreateInviteForUserError::Generic {
-                                    message: "CreateInvite can only be called on a user session"
-                                        .to_string(),
-                                },
-                                message: "CreateInvite can only be called on a user session"
-                                    .to_string(),
-                            },
-                        ));
-                    }
-
-                    let user_id = auth_session
-                        .target_id
-                        .parse::<serenity::all::UserId>()
-                        .map_err(|e| {
-                            InfernoplexErrorResponse::new(
-                                StatusCode::FORBIDDEN,
-                                HeaderMap::new(),
-                                InfernoplexError::CreateInvite {
-                                    err: CreateInviteForUserError::Generic {
-                                        message: format!("Invalid user ID: {}", e),
-                                    },
-                                    message: format!("Invalid user ID: {}", e),
-                                },
-                            )
-                        })?;
-
-                    Some(user_id)
-                } else {
-                    let mut headers = HeaderMap::new();
-                    headers.insert("X-Session-Invalid", "1".parse().unwrap());
-                    return Err(InfernoplexErrorResponse::new(
-                        StatusCode::FORBIDDEN,
-                        headers,
-                        InfernoplexError::CreateInvite {
-                            err: CreateInviteForUserError::Generic {
-                                message: "Invalid session token".to_string(),
-                            },
-                            message: "Invalid session token".to_string(),
-                        },
-                    ));
-                }
+                Some(user_id)
             } else {
                 None
             };
 
+            if let Err(retry_after) =
+                crate::shadowclaw::ratelimit::check_invite_ratelimit(guild_id, user_id)
+            {
+                return Err(create_invite_error(CreateInviteForUserError::RateLimited {
+                    retry_after: retry_after.as_secs() + 1,
+                }));
+            }
+
             let created_invite = crate::shadowclaw::invite::create_invite_for_user(
                 &state.cache_http,
                 &state.pool,
                 guild_id,
                 user_id,
                 false,
+                answers,
             )
             .await;
 
@@ -271,14 +462,7 @@ async fn query(
 
             match created_invite {
                 Ok(invite) => Ok(InfernoplexResponse::CreateInvite { result: invite }),
-                Err(e) => Err(InfernoplexErrorResponse::new(
-                    StatusCode::INTERNAL_SERVER_ERROR,
-                    HeaderMap::new(),
-                    InfernoplexError::CreateInvite {
-                        err: e.clone(),
-                        message: e.to_string(),
-                    },
-                )),
+                Err(e) => Err(create_invite_error(e)),
             }
         }
         InfernoplexQuery::ResolveInvite {
@@ -305,5 +489,264 @@ async fn query(
                 )),
             }
         }
+        InfernoplexQuery::ManageWhitelist {
+            session,
+            guild_id,
+            action,
+        } => {
+            let err = |status: StatusCode, headers: HeaderMap, message: String| {
+                InfernoplexErrorResponse::new(
+                    status,
+                    headers,
+                    InfernoplexError::ManageWhitelist { message },
+                )
+            };
+
+            let user_id = super::auth::user_from_session(&state.pool, &session)
+                .await
+                .map_err(|(status, headers, message)| err(status, headers, message))?;
+
+            crate::shadowclaw::perms::check_permission(
+                &state.pool,
+                guild_id,
+                user_id,
+                "server.whitelist",
+            )
+            .await
+            .map_err(|e| err(StatusCode::FORBIDDEN, HeaderMap::new(), e.to_string()))?;
+
+            let users = match action {
+                WhitelistAction::List {} => {
+                    crate::shadowclaw::whitelist::get_whitelist(&state.pool, guild_id).await
+                }
+                WhitelistAction::Add { user_id } => {
+                    crate::shadowclaw::whitelist::add_to_whitelist(&state.pool, guild_id, user_id)
+                        .await
+                }
+                WhitelistAction::Remove { user_id } => {
+                    crate::shadowclaw::whitelist::remove_from_whitelist(
+                        &state.pool,
+                        guild_id,
+                        user_id,
+                    )
+                    .await
+                }
+            }
+            .map_err(|e| err(StatusCode::BAD_REQUEST, HeaderMap::new(), e.to_string()))?;
+
+            Ok(InfernoplexResponse::ManageWhitelist { users })
+        }
+        InfernoplexQuery::GetInviteLogs {
+            session,
+            guild_id,
+            limit,
+            offset,
+        } => {
+            let err = |status: StatusCode, headers: HeaderMap, message: String| {
+                InfernoplexErrorResponse::new(
+                    status,
+                    headers,
+                    InfernoplexError::GetInviteLogs { message },
+                )
+            };
+
+            let user_id = super::auth::user_from_session(&state.pool, &session)
+                .await
+                .map_err(|(status, headers, message)| err(status, headers, message))?;
+
+            crate::shadowclaw::perms::check_permission(
+                &state.pool,
+                guild_id,
+                user_id,
+                "server.view_invite_logs",
+            )
+            .await
+            .map_err(|e| err(StatusCode::FORBIDDEN, HeaderMap::new(), e.to_string()))?;
+
+            let logs = crate::shadowclaw::invitelog::get_invite_logs(
+                &state.pool,
+                guild_id,
+                limit.unwrap_or(25),
+                offset.unwrap_or(0),
+            )
+            .await
+            .map_err(|e| err(StatusCode::BAD_REQUEST, HeaderMap::new(), e.to_string()))?;
+
+            Ok(InfernoplexResponse::GetInviteLogs { logs })
+        }
+        InfernoplexQuery::ManageBlacklist {
+            session,
+            guild_id,
+            action,
+        } => {
+            let err = |status: StatusCode, headers: HeaderMap, message: String| {
+                InfernoplexErrorResponse::new(
+                    status,
+                    headers,
+                    InfernoplexError::ManageBlacklist { message },
+                )
+            };
+
+            let user_id = super::auth::user_from_session(&state.pool, &session)
+                .await
+                .map_err(|(status, headers, message)| err(status, headers, message))?;
+
+            crate::shadowclaw::perms::check_permission(
+                &state.pool,
+                guild_id,
+                user_id,
+                "server.blacklist",
+            )
+            .await
+            .map_err(|e| err(StatusCode::FORBIDDEN, HeaderMap::new(), e.to_string()))?;
+
+            let entries = match action {
+                BlacklistAction::List {} => {
+                    crate::shadowclaw::blacklist::get_blacklist(&state.pool, guild_id).await
+                }
+                BlacklistAction::Add {
+                    user_id: target_id,
+                    reason,
+                    expires_at,
+                } => {
+                    crate::shadowclaw::blacklist::add_to_blacklist(
+                        &state.cache_http.http,
+                        &state.pool,
+                        guild_id,
+                        target_id,
+                        reason,
+                        expires_at,
+                        user_id,
+                    )
+                    .await
+                }
+                BlacklistAction::Remove { user_id: target_id } => {
+                    crate::shadowclaw::blacklist::remove_from_blacklist(
+                        &state.pool,
+                        guild_id,
+                        target_id,
+                    )
+                    .await
+                }
+            }
+            .map_err(|e| err(StatusCode::BAD_REQUEST, HeaderMap::new(), e.to_string()))?;
+
+            Ok(InfernoplexResponse::ManageBlacklist { entries })
+        }
+        InfernoplexQuery::ManageInviteRules {
+            session,
+            guild_id,
+            rules,
+        } => {
+            let err = |status: StatusCode, headers: HeaderMap, message: String| {
+                InfernoplexErrorResponse::new(
+                    status,
+                    headers,
+                    InfernoplexError::ManageInviteRules { message },
+                )
+            };
+
+            let user_id = super::auth::user_from_session(&state.pool, &session)
+                .await
+                .map_err(|(status, headers, message)| err(status, headers, message))?;
+
+            crate::shadowclaw::perms::check_permission(
+                &state.pool,
+                guild_id,
+                user_id,
+                "server.edit",
+            )
+            .await
+            .map_err(|e| err(StatusCode::FORBIDDEN, HeaderMap::new(), e.to_string()))?;
+
+            let rules = match rules {
+                Some(rules) => {
+                    crate::shadowclaw::inviterules::set_invite_rules(
+                        &state.pool,
+                        guild_id,
+                        rules,
+                        state.intents,
+                    )
+                    .await
+                }
+                None => {
+                    crate::shadowclaw::inviterules::get_invite_rules(&state.pool, guild_id).await
+                }
+            }
+            .map_err(|e| err(StatusCode::BAD_REQUEST, HeaderMap::new(), e.to_string()))?;
+
+            Ok(InfernoplexResponse::ManageInviteRules { rules })
+        }
+        InfernoplexQuery::ManageInviteSchedule {
+            session,
+            guild_id,
+            action,
+        } => {
+            let err = |status: StatusCode, headers: HeaderMap, message: String| {
+                InfernoplexErrorResponse::new(
+                    status,
+                    headers,
+                    InfernoplexError::ManageInviteSchedule { message },
+                )
+            };
+
+            let user_id = super::auth::user_from_session(&state.pool, &session)
+                .await
+                .map_err(|(status, headers, message)| err(status, headers, message))?;
+
+            crate::shadowclaw::perms::check_permission(
+                &state.pool,
+                guild_id,
+                user_id,
+                "server.edit",
+            )
+            .await
+            .map_err(|e| err(StatusCode::FORBIDDEN, HeaderMap::new(), e.to_string()))?;
+
+            let schedule = match action {
+                InviteScheduleAction::Get {} => {
+                    crate::shadowclaw::inviteschedule::get_invite_schedule(&state.pool, guild_id)
+                        .await
+                }
+                InviteScheduleAction::Set { schedule } => {
+                    crate::shadowclaw::inviteschedule::set_invite_schedule(
+                        &state.pool,
+                        guild_id,
+                        Some(schedule),
+                    )
+                    .await
+                }
+                InviteScheduleAction::Clear {} => {
+                    crate::shadowclaw::inviteschedule::set_invite_schedule(
+                        &state.pool,
+                        guild_id,
+                        None,
+                    )
+                    .await
+                }
+            }
+            .map_err(|e| err(StatusCode::BAD_REQUEST, HeaderMap::new(), e.to_string()))?;
+
+            Ok(InfernoplexResponse::ManageInviteSchedule { schedule })
+        }
+        InfernoplexQuery::GetServerStats { guild_id, days } => {
+            let samples = crate::shadowclaw::memberstats::get_member_stats(
+                &state.pool,
+                guild_id,
+                days.unwrap_or(30),
+            )
+            .await
+            .map_err(|e| {
+                InfernoplexErrorResponse::new(
+                    StatusCode::BAD_REQUEST,
+                    HeaderMap::new(),
+                    InfernoplexError::GetServerStats {
+                        message: e.to_string(),
+                    },
+                )
+            })?;
+
+            Ok(InfernoplexResponse::GetServerStats { samples })
+        }
     }
 }
@@ -0,0 +1,11 @@
+pub async fn bot_missing(ctx: &serenity::all::Context) -> Result<(), crate::Error> {
+    let data = ctx.data::<crate::Data>();
+
+    let hidden = crate::shadowclaw::botmissing::hide_missing(&ctx.http, &data.pool).await?;
+
+    if hidden > 0 {
+        log::info!("Made {} servers missing Infinity List private", hidden);
+    }
+
+    Ok(())
+}
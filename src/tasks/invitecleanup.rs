@@ -0,0 +1,80 @@
+use crate::shadowclaw::invitespec::InviteSpec;
+use serenity::all::ChannelId;
+
+/// The maximum number of invites revoked per run, so a backlog doesn't hog the ratelimits
+const MAX_REVOCATIONS_PER_RUN: usize = 250;
+
+pub async fn invite_cleanup(ctx: &serenity::all::Context) -> Result<(), crate::Error> {
+    let data = ctx.data::<crate::Data>();
+    let cleanup = &crate::config::CONFIG.invite_cleanup;
+
+    // Invites can only be revoked in servers the bot is still in
+    let guild_ids = ctx
+        .cache
+        .guilds()
+        .into_iter()
+        .map(|g| g.to_string())
+        .collect::<Vec<_>>();
+
+    let invites = sqlx::query!(
+        "SELECT si.code, si.channel_id, si.uses, si.max_uses, si.expires_at, si.created_at, s.invite FROM server_invites si
+        INNER JOIN servers s ON s.server_id = si.server_id
+        WHERE NOT si.deleted AND si.server_id = ANY($1)
+        ORDER BY si.created_at",
+        &guild_ids
+    )
+    .fetch_all(&data.pool)
+    .await?;
+
+    let now = chrono::Utc::now();
+    let mut revoked = 0;
+
+    for invite in invites {
+        if revoked >= MAX_REVOCATIONS_PER_RUN {
+            break;
+        }
+
+        // Invites whose channel is no longer used by the servers invite spec are orphaned
+        let orphaned = match InviteSpec::from_stored(&invite.invite) {
+            Ok(spec) => match (spec.per_user(), invite.channel_id.parse::<ChannelId>()) {
+                (Some(per_user), Ok(channel_id)) => {
+                    !per_user.channel_ids().any(|c| c == channel_id)
+                }
+                _ => true,
+            },
+            Err(_) => false,
+        };
+
+        let reason = if orphaned {
+            "Invite is no longer used by the server's invite settings"
+        } else if cleanup.revoke_used && invite.max_uses > 0 && invite.uses >= invite.max_uses {
+            "Invite has been used up"
+        } else if invite.expires_at.is_some_and(|e| e <= now) {
+            "Invite has expired"
+        } else if cleanup.max_lifetime_days > 0
+            && invite.created_at + chrono::Duration::days(cleanup.max_lifetime_days.into()) <= now
+        {
+            "Invite is older than the maximum invite lifetime"
+        } else {
+            continue;
+        };
+
+        match crate::shadowclaw::invitestore::revoke_invite(
+            &ctx.http,
+            &data.pool,
+            &invite.code,
+            reason,
+        )
+        .await
+        {
+            Ok(()) => revoked += 1,
+            Err(e) => log::warn!("Failed to revoke invite {}: {}", invite.code, e),
+        }
+    }
+
+    if revoked > 0 {
+        log::info!("Revoked {} per-user invites", revoked);
+    }
+
+    Ok(())
+}
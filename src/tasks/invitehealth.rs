@@ -0,0 +1,60 @@
+use crate::shadowclaw::invitespec::InviteSpec;
+use serenity::all::GuildId;
+
+pub async fn invite_health(ctx: &serenity::all::Context) -> Result<(), crate::Error> {
+    let data = ctx.data::<crate::Data>();
+    let cache_http = botox::cache::CacheHttpImpl::from_ctx(ctx);
+
+    let guild_ids = ctx
+        .cache
+        .guilds()
+        .into_iter()
+        .map(|g| g.to_string())
+        .collect::<Vec<_>>();
+
+    let servers = sqlx::query!(
+        "SELECT server_id, invite FROM servers WHERE server_id = ANY($1)",
+        &guild_ids
+    )
+    .fetch_all(&data.pool)
+    .await?;
+
+    for server in servers {
+        let guild_id = server.server_id.parse::<GuildId>()?;
+
+        let result = match InviteSpec::from_stored(&server.invite) {
+            Ok(mut spec) => {
+                if let Err(e) = crate::shadowclaw::invitehealth::clear_ended_event(
+                    &ctx.http,
+                    &data.pool,
+                    guild_id,
+                    &server.invite,
+                    &mut spec,
+                )
+                .await
+                {
+                    log::warn!("Failed to check the scheduled event of {}: {}", guild_id, e);
+                }
+
+                crate::shadowclaw::invitehealth::check_invite_health(
+                    &cache_http,
+                    &data.pool,
+                    guild_id,
+                    &spec,
+                )
+                .await
+            }
+            Err(e) => Err(format!("The invite of this server could not be parsed: {}", e).into()),
+        };
+
+        if let Err(e) = crate::shadowclaw::invitehealth::record_invite_health(
+            &ctx.http, &data.pool, guild_id, result,
+        )
+        .await
+        {
+            log::error!("Failed to record invite health of {}: {}", guild_id, e);
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,16 @@
+pub async fn member_stats(ctx: &serenity::all::Context) -> Result<(), crate::Error> {
+    let data = ctx.data::<crate::Data>();
+
+    let guild_ids = ctx
+        .cache
+        .guilds()
+        .into_iter()
+        .map(|g| g.to_string())
+        .collect::<Vec<_>>();
+
+    // Counts in servers are kept up to date by serversync
+    crate::shadowclaw::memberstats::record_samples(&data.pool, &guild_ids).await?;
+    crate::shadowclaw::memberstats::downsample(&data.pool).await?;
+
+    Ok(())
+}
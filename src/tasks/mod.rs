@@ -1,14 +1,49 @@
+pub mod botmissing;
+pub mod invitecleanup;
+pub mod invitehealth;
+pub mod memberstats;
 pub mod serversync;
 
 use botox::taskman::Task;
 use futures_util::FutureExt;
 
 pub fn tasks() -> Vec<Task> {
-    vec![Task {
-        name: "serversync",
-        description: "Synchronises server data with the database",
-        duration: std::time::Duration::from_secs(60),
-        enabled: true,
-        run: Box::new(move |ctx| crate::tasks::serversync::server_sync(ctx).boxed()),
-    }]
+    vec![
+        Task {
+            name: "serversync",
+            description: "Synchronises server data with the database",
+            duration: std::time::Duration::from_secs(60),
+            enabled: true,
+            run: Box::new(move |ctx| crate::tasks::serversync::server_sync(ctx).boxed()),
+        },
+        Task {
+            name: "invitehealth",
+            description: "Checks that the invites of all servers still work",
+            duration: std::time::Duration::from_secs(60 * 30),
+            enabled: true,
+            run: Box::new(move |ctx| crate::tasks::invitehealth::invite_health(ctx).boxed()),
+        },
+        Task {
+            name: "invitecleanup",
+            description: "Revokes used, expired and orphaned per-user invites",
+            duration: std::time::Duration::from_secs(60 * 10),
+            enabled: true,
+            run: Box::new(move |ctx| crate::tasks::invitecleanup::invite_cleanup(ctx).boxed()),
+        },
+        Task {
+            name: "memberstats",
+            description: "Records the member counts of all servers for growth graphs",
+            duration: std::time::Duration::from_secs(60 * 60),
+            enabled: true,
+            run: Box::new(move |ctx| crate::tasks::memberstats::member_stats(ctx).boxed()),
+        },
+        Task {
+            name: "botmissing",
+            description:
+                "Makes servers that Infinity List was removed from private after a grace period",
+            duration: std::time::Duration::from_secs(60 * 15),
+            enabled: true,
+            run: Box::new(move |ctx| crate::tasks::botmissing::bot_missing(ctx).boxed()),
+        },
+    ]
 }
@@ -1,5 +1,197 @@
+use crate::shadowclaw::stats::GuildStats;
+use once_cell::sync::Lazy;
+use serenity::all::GuildId;
+use std::collections::{HashMap, HashSet};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// The maximum number of servers updated in a single query
+const BATCH_SIZE: usize = 500;
+
+/// How long approximate counts fetched from Discord are reused before being fetched again
+const APPROXIMATE_COUNTS_TTL: Duration = Duration::from_secs(20 * 60);
+
+/// The maximum number of guilds whose approximate counts are fetched per run, so large bots don't hog the ratelimits
+const MAX_COUNT_FETCHES_PER_RUN: usize = 50;
+
+/// Approximate total and online member counts of partially cached guilds, along with when they were fetched
+static APPROXIMATE_COUNTS: Lazy<Mutex<HashMap<GuildId, (Instant, i32, i32)>>> =
+    Lazy::new(|| Mutex::new(HashMap::new()));
+
+/// The listing data of a server that is kept in sync with Discord
+#[derive(PartialEq)]
+struct ServerData {
+    name: String,
+    total_members: i32,
+    online_members: i32,
+    nsfw: bool,
+}
+
 pub async fn server_sync(ctx: &serenity::all::Context) -> Result<(), crate::Error> {
-    // Loop over every single guild we currently have
-    for _guild in ctx.cache.guilds() {}
+    let data = ctx.data::<crate::Data>();
+
+    // Collect everything from the cache first, cache references must not be held across awaits
+    let mut cached = HashMap::new();
+    let mut partially_cached = HashSet::new();
+
+    for guild_id in ctx.cache.guilds() {
+        let Some(guild) = ctx.cache.guild(guild_id) else {
+            continue;
+        };
+
+        let stats = GuildStats::from_guild(&guild);
+
+        if !GuildStats::is_fully_cached(&guild, data.intents) {
+            partially_cached.insert(guild_id);
+        }
+
+        cached.insert(
+            guild_id.to_string(),
+            ServerData {
+                name: stats.name,
+                total_members: i32::try_from(stats.total_members).unwrap_or(i32::MAX),
+                online_members: i32::try_from(stats.online_members).unwrap_or(i32::MAX),
+                nsfw: stats.nsfw,
+            },
+        );
+    }
+
+    let listed = sqlx::query!(
+        "SELECT server_id, name, total_members, online_members, nsfw FROM servers WHERE server_id = ANY($1)",
+        &cached.keys().cloned().collect::<Vec<_>>()
+    )
+    .fetch_all(&data.pool)
+    .await?;
+
+    let listed = listed
+        .into_iter()
+        .map(|r| {
+            (
+                r.server_id,
+                ServerData {
+                    name: r.name,
+                    total_members: r.total_members,
+                    online_members: r.online_members,
+                    nsfw: r.nsfw,
+                },
+            )
+        })
+        .collect::<HashMap<_, _>>();
+
+    // Use approximate counts for listed guilds whose members or presences are not fully cached
+    APPROXIMATE_COUNTS
+        .lock()
+        .unwrap()
+        .retain(|guild_id, _| partially_cached.contains(guild_id));
+
+    let mut fetches = 0;
+
+    for guild_id in partially_cached {
+        let key = guild_id.to_string();
+
+        if !listed.contains_key(&key) {
+            continue;
+        }
+
+        let known = APPROXIMATE_COUNTS.lock().unwrap().get(&guild_id).copied();
+
+        let counts = match known {
+            Some((fetched_at, total, online)) if fetched_at.elapsed() < APPROXIMATE_COUNTS_TTL => {
+                Some((total, online))
+            }
+            // Out of fetches for this run, stale counts are still better than the cached ones
+            _ if fetches >= MAX_COUNT_FETCHES_PER_RUN => {
+                known.map(|(_, total, online)| (total, online))
+            }
+            _ => {
+                fetches += 1;
+
+                match approximate_counts(&ctx.http, guild_id).await {
+                    Ok((total, online)) => {
+                        APPROXIMATE_COUNTS
+                            .lock()
+                            .unwrap()
+                            .insert(guild_id, (Instant::now(), total, online));
+
+                        Some((total, online))
+                    }
+                    Err(e) => {
+                        log::warn!("Failed to fetch approximate counts of {}: {}", guild_id, e);
+                        known.map(|(_, total, online)| (total, online))
+                    }
+                }
+            }
+        };
+
+        // Without approximate counts, keep the listed counts rather than the incomplete cached ones
+        let (total_members, online_members) = match counts {
+            Some(counts) => counts,
+            None => match listed.get(&key) {
+                Some(listed) => (listed.total_members, listed.online_members),
+                None => continue,
+            },
+        };
+
+        if let Some(server) = cached.get_mut(&key) {
+            server.total_members = total_members;
+            server.online_members = online_members;
+        }
+    }
+
+    let changed = cached
+        .into_iter()
+        .filter(|(server_id, server)| listed.get(server_id).is_some_and(|l| l != server))
+        .collect::<Vec<_>>();
+
+    for batch in changed.chunks(BATCH_SIZE) {
+        let mut server_ids = Vec::with_capacity(batch.len());
+        let mut names = Vec::with_capacity(batch.len());
+        let mut total_members = Vec::with_capacity(batch.len());
+        let mut online_members = Vec::with_capacity(batch.len());
+        let mut nsfw = Vec::with_capacity(batch.len());
+
+        for (server_id, server) in batch {
+            server_ids.push(server_id.clone());
+            names.push(server.name.clone());
+            total_members.push(server.total_members);
+            online_members.push(server.online_members);
+            nsfw.push(server.nsfw);
+        }
+
+        sqlx::query!(
+            "UPDATE servers SET name = u.name, total_members = u.total_members, online_members = u.online_members, nsfw = u.nsfw
+            FROM UNNEST($1::text[], $2::text[], $3::integer[], $4::integer[], $5::boolean[]) AS u(server_id, name, total_members, online_members, nsfw)
+            WHERE servers.server_id = u.server_id",
+            &server_ids,
+            &names,
+            &total_members,
+            &online_members,
+            &nsfw
+        )
+        .execute(&data.pool)
+        .await?;
+    }
+
+    if !changed.is_empty() {
+        log::info!("Synced {} servers", changed.len());
+    }
+
     Ok(())
 }
+
+/// Returns the approximate total and online member counts of a guild
+async fn approximate_counts(
+    http: &serenity::all::Http,
+    guild_id: GuildId,
+) -> Result<(i32, i32), crate::Error> {
+    let guild = http.get_guild_with_counts(guild_id).await?;
+
+    let (Some(total), Some(online)) = (
+        guild.approximate_member_count,
+        guild.approximate_presence_count,
+    ) else {
+        return Err("Discord did not return approximate counts".into());
+    };
+
+    Ok((i32::try_from(total)?, i32::try_from(online)?))
+}